@@ -0,0 +1,50 @@
+"""alarm_rules: threshold alarms evaluated by the desktop API server
+
+Revision ID: 0002_alarm_rules
+Revises: 0001_init_mysql
+Create Date: 2026-10-15
+"""
+
+from __future__ import annotations
+
+import sqlalchemy as sa
+from alembic import op
+from sqlalchemy.dialects import mysql
+
+revision = "0002_alarm_rules"
+down_revision = "0001_init_mysql"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    # -----------------------------------------------------------------------
+    # alarm_rules: one threshold per (device_uid, metric). device_uid is not a
+    # FK so a rule can be created before the device first reports.
+    # -----------------------------------------------------------------------
+    op.create_table(
+        "alarm_rules",
+        sa.Column("id", sa.BigInteger(), primary_key=True),
+        sa.Column("device_uid", sa.String(length=128), nullable=False),
+        sa.Column("metric", sa.String(length=255), nullable=False),
+        sa.Column("op", sa.String(length=8), nullable=False),
+        sa.Column("threshold", mysql.DOUBLE(), nullable=False),
+        sa.Column(
+            "hysteresis",
+            mysql.DOUBLE(),
+            server_default=sa.text("0"),
+            nullable=False,
+        ),
+        sa.Column(
+            "created_at",
+            mysql.DATETIME(fsp=6),
+            server_default=sa.text("CURRENT_TIMESTAMP(6)"),
+            nullable=False,
+        ),
+    )
+    op.create_index("ix_alarm_rules_device_uid", "alarm_rules", ["device_uid"])
+
+
+def downgrade() -> None:
+    op.drop_index("ix_alarm_rules_device_uid", table_name="alarm_rules")
+    op.drop_table("alarm_rules")
@@ -3,7 +3,7 @@ from __future__ import annotations
 from datetime import datetime
 
 from sqlalchemy import BigInteger, Boolean, ForeignKey, Index, String, func
-from sqlalchemy.dialects.mysql import DATETIME, JSON, LONGBLOB
+from sqlalchemy.dialects.mysql import DATETIME, DOUBLE, JSON, LONGBLOB
 from sqlalchemy.orm import DeclarativeBase, Mapped, mapped_column, relationship
 
 
@@ -87,3 +87,31 @@ class TelemetrySample(Base):
 
 
 Index("ix_telemetry_device_ts", TelemetrySample.device_id, TelemetrySample.ts)
+
+
+class AlarmRule(Base):
+    """
+    Alarm rules (evaluated by the desktop API server, not this backend):
+    - fires when metric <op> threshold; clears once it is back past the
+      threshold by hysteresis
+    - device_uid is not a FK so rules can exist before the device reports
+    """
+
+    __tablename__ = "alarm_rules"
+
+    id: Mapped[int] = mapped_column(BigInteger, primary_key=True)
+    device_uid: Mapped[str] = mapped_column(String(128), nullable=False, index=True)
+    metric: Mapped[str] = mapped_column(String(255), nullable=False)
+    op: Mapped[str] = mapped_column(String(8), nullable=False)
+    threshold: Mapped[float] = mapped_column(DOUBLE, nullable=False)
+    hysteresis: Mapped[float] = mapped_column(
+        DOUBLE,
+        server_default="0",
+        nullable=False,
+    )
+
+    created_at: Mapped[datetime] = mapped_column(
+        DATETIME(fsp=6),
+        server_default=func.now(),
+        nullable=False,
+    )
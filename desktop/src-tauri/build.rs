@@ -1,3 +1,5 @@
 fn main() {
+  // `sqlx::migrate!` embeds these at compile time.
+  println!("cargo:rerun-if-changed=migrations");
   tauri_build::build()
 }
@@ -0,0 +1,31 @@
+-- Same schema as backend/alembic 0001_init_mysql. IF NOT EXISTS keeps this a
+-- no-op on databases Alembic already created.
+CREATE TABLE IF NOT EXISTS devices (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  device_uid VARCHAR(128) NOT NULL,
+  name VARCHAR(255) NULL,
+  model VARCHAR(255) NULL,
+  firmware_version VARCHAR(255) NULL,
+  metadata_json JSON NULL,
+  created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+  updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+  CONSTRAINT uq_devices_device_uid UNIQUE (device_uid),
+  INDEX ix_devices_device_uid (device_uid)
+);
+
+CREATE TABLE IF NOT EXISTS telemetry_samples (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  device_id BIGINT NOT NULL,
+  ts DATETIME(6) NOT NULL,
+  metrics_json JSON NOT NULL,
+  quality_json JSON NULL,
+  crc_ok BOOLEAN NULL,
+  frame_seq BIGINT NULL,
+  raw_frame LONGBLOB NULL,
+  source VARCHAR(32) NULL,
+  created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+  FOREIGN KEY (device_id) REFERENCES devices (id) ON DELETE CASCADE,
+  INDEX ix_telemetry_samples_device_id (device_id),
+  INDEX ix_telemetry_samples_ts (ts),
+  INDEX ix_telemetry_device_ts (device_id, ts)
+);
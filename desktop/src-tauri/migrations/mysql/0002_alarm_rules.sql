@@ -0,0 +1,11 @@
+-- Same schema as backend/alembic 0002_alarm_rules.
+CREATE TABLE IF NOT EXISTS alarm_rules (
+  id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  device_uid VARCHAR(128) NOT NULL,
+  metric VARCHAR(255) NOT NULL,
+  op VARCHAR(8) NOT NULL,
+  threshold DOUBLE NOT NULL,
+  hysteresis DOUBLE NOT NULL DEFAULT 0,
+  created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+  INDEX ix_alarm_rules_device_uid (device_uid)
+);
@@ -0,0 +1,26 @@
+-- PostgreSQL equivalent of backend/alembic 0001_init_mysql.
+CREATE TABLE IF NOT EXISTS devices (
+  id BIGSERIAL PRIMARY KEY,
+  device_uid VARCHAR(128) NOT NULL UNIQUE,
+  name VARCHAR(255),
+  model VARCHAR(255),
+  firmware_version VARCHAR(255),
+  metadata_json JSONB,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS telemetry_samples (
+  id BIGSERIAL PRIMARY KEY,
+  device_id BIGINT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+  ts TIMESTAMPTZ NOT NULL,
+  metrics_json JSONB NOT NULL,
+  quality_json JSONB,
+  crc_ok BOOLEAN,
+  frame_seq BIGINT,
+  raw_frame BYTEA,
+  source VARCHAR(32),
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS ix_telemetry_samples_ts ON telemetry_samples (ts);
+CREATE INDEX IF NOT EXISTS ix_telemetry_device_ts ON telemetry_samples (device_id, ts);
@@ -0,0 +1,11 @@
+-- PostgreSQL equivalent of backend/alembic 0002_alarm_rules.
+CREATE TABLE IF NOT EXISTS alarm_rules (
+  id BIGSERIAL PRIMARY KEY,
+  device_uid VARCHAR(128) NOT NULL,
+  metric VARCHAR(255) NOT NULL,
+  op VARCHAR(8) NOT NULL,
+  threshold DOUBLE PRECISION NOT NULL,
+  hysteresis DOUBLE PRECISION NOT NULL DEFAULT 0,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS ix_alarm_rules_device_uid ON alarm_rules (device_uid);
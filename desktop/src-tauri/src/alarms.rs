@@ -0,0 +1,255 @@
+//! Threshold alarms evaluated against the realtime telemetry broadcast.
+//! Rules are stored in `alarm_rules`; transitions go out on their own channel.
+
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+use crate::api_server::TelemetryEvent;
+use crate::telemetry_db::AlarmRuleRow;
+
+/// Comparison that makes a rule fire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlarmOp {
+  Gt,
+  Lt,
+  Eq,
+}
+
+impl AlarmOp {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      AlarmOp::Gt => "gt",
+      AlarmOp::Lt => "lt",
+      AlarmOp::Eq => "eq",
+    }
+  }
+}
+
+/// Body of `POST /api/alarms`.
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct NewAlarmRule {
+  pub device_uid: String,
+  /// Top-level key in the sample's `metrics`.
+  pub metric: String,
+  pub op: AlarmOp,
+  pub threshold: f64,
+  /// How far back past `threshold` the value must go before the alarm
+  /// clears (for `eq`: how far from it). Default 0.
+  #[serde(default)]
+  pub hysteresis: f64,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct AlarmRule {
+  pub id: i64,
+  pub device_uid: String,
+  pub metric: String,
+  pub op: AlarmOp,
+  pub threshold: f64,
+  pub hysteresis: f64,
+}
+
+impl AlarmRule {
+  pub fn from_row(row: AlarmRuleRow) -> Option<Self> {
+    let op = match row.op.as_str() {
+      "gt" => AlarmOp::Gt,
+      "lt" => AlarmOp::Lt,
+      "eq" => AlarmOp::Eq,
+      other => {
+        eprintln!("[api] skipping alarm rule {} with unknown op {other:?}", row.id);
+        return None;
+      }
+    };
+    Some(AlarmRule {
+      id: row.id,
+      device_uid: row.device_uid,
+      metric: row.metric,
+      op,
+      threshold: row.threshold,
+      hysteresis: row.hysteresis,
+    })
+  }
+
+  /// Whether the rule is firing after seeing `value`. Once firing, the
+  /// value has to move `hysteresis` past the threshold to clear, so a noisy
+  /// reading doesn't flap the alarm.
+  fn firing_after(&self, firing: bool, value: f64) -> bool {
+    let margin = if firing { self.hysteresis } else { 0.0 };
+    match self.op {
+      AlarmOp::Gt => value > self.threshold - margin,
+      AlarmOp::Lt => value < self.threshold + margin,
+      AlarmOp::Eq => (value - self.threshold).abs() <= margin.max(f64::EPSILON),
+    }
+  }
+}
+
+/// A rule with its current state, as listed by `GET /api/alarms`.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct AlarmStatus {
+  #[serde(flatten)]
+  pub rule: AlarmRule,
+  pub firing: bool,
+  /// Most recent value seen for the metric.
+  pub value: Option<f64>,
+  /// Timestamp of the sample that last fired or cleared the alarm.
+  pub since: Option<String>,
+}
+
+/// Sent on `/ws/alarms` when a rule starts or stops firing.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct AlarmEvent {
+  /// `alarm:fired` or `alarm:cleared`.
+  pub event: &'static str,
+  pub rule_id: i64,
+  pub device_uid: String,
+  pub metric: String,
+  pub op: AlarmOp,
+  pub threshold: f64,
+  pub value: f64,
+  /// Timestamp of the sample that caused the transition.
+  pub ts: String,
+}
+
+/// Rule states plus the channel their transitions are published on.
+pub struct AlarmEngine {
+  states: Mutex<Vec<AlarmStatus>>,
+  tx: broadcast::Sender<AlarmEvent>,
+}
+
+impl AlarmEngine {
+  pub fn new(rules: Vec<AlarmRule>) -> Self {
+    let (tx, _rx) = broadcast::channel(256);
+    let states = rules.into_iter().map(AlarmStatus::idle).collect();
+    AlarmEngine {
+      states: Mutex::new(states),
+      tx,
+    }
+  }
+
+  pub fn subscribe(&self) -> broadcast::Receiver<AlarmEvent> {
+    self.tx.subscribe()
+  }
+
+  pub fn add(&self, rule: AlarmRule) -> AlarmStatus {
+    let status = AlarmStatus::idle(rule);
+    if let Ok(mut states) = self.states.lock() {
+      states.push(status.clone());
+    }
+    status
+  }
+
+  pub fn remove(&self, id: i64) {
+    if let Ok(mut states) = self.states.lock() {
+      states.retain(|status| status.rule.id != id);
+    }
+  }
+
+  pub fn statuses(&self) -> Vec<AlarmStatus> {
+    self.states.lock().map(|states| states.clone()).unwrap_or_default()
+  }
+
+  /// Updates every rule watching `event`'s device and publishes transitions.
+  /// Samples without a numeric value for a rule's metric leave it unchanged.
+  pub fn evaluate(&self, event: &TelemetryEvent) {
+    let Some(device_uid) = event.device_uid.as_deref() else {
+      return;
+    };
+    let Ok(mut states) = self.states.lock() else {
+      return;
+    };
+    for status in states.iter_mut().filter(|status| status.rule.device_uid == device_uid) {
+      let Some(value) = event.metrics.get(&status.rule.metric).and_then(|v| v.as_f64()) else {
+        continue;
+      };
+      status.value = Some(value);
+      let firing = status.rule.firing_after(status.firing, value);
+      if firing == status.firing {
+        continue;
+      }
+      status.firing = firing;
+      status.since = Some(event.ts.clone());
+      let rule = &status.rule;
+      let kind = if firing { "alarm:fired" } else { "alarm:cleared" };
+      eprintln!("[api] {kind} rule={} device={device_uid} {}={value}", rule.id, rule.metric);
+      // No subscribers is fine; the state is still kept for GET /api/alarms.
+      let _ = self.tx.send(AlarmEvent {
+        event: kind,
+        rule_id: rule.id,
+        device_uid: rule.device_uid.clone(),
+        metric: rule.metric.clone(),
+        op: rule.op,
+        threshold: rule.threshold,
+        value,
+        ts: event.ts.clone(),
+      });
+    }
+  }
+}
+
+impl AlarmStatus {
+  fn idle(rule: AlarmRule) -> Self {
+    AlarmStatus {
+      rule,
+      firing: false,
+      value: None,
+      since: None,
+    }
+  }
+}
+
+/// Evaluates every event published on the telemetry broadcast until it closes.
+pub async fn run_alarms(engine: Arc<AlarmEngine>, mut rx: broadcast::Receiver<TelemetryEvent>) {
+  loop {
+    match rx.recv().await {
+      Ok(event) => engine.evaluate(&event),
+      Err(broadcast::error::RecvError::Lagged(skipped)) => {
+        eprintln!("[api] alarm evaluation fell behind, skipped {skipped} samples");
+      }
+      Err(broadcast::error::RecvError::Closed) => break,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sample(value: f64) -> TelemetryEvent {
+    TelemetryEvent {
+      ts: "2024-01-01T00:00:00Z".to_string(),
+      device_id: None,
+      device_uid: Some("dev-1".to_string()),
+      metrics: serde_json::json!({ "temp": value }),
+      quality: None,
+    }
+  }
+
+  #[test]
+  fn gt_rule_fires_and_clears_with_hysteresis() {
+    let engine = AlarmEngine::new(vec![AlarmRule {
+      id: 1,
+      device_uid: "dev-1".to_string(),
+      metric: "temp".to_string(),
+      op: AlarmOp::Gt,
+      threshold: 50.0,
+      hysteresis: 5.0,
+    }]);
+    let mut rx = engine.subscribe();
+
+    engine.evaluate(&sample(49.0));
+    assert!(rx.try_recv().is_err());
+    engine.evaluate(&sample(51.0));
+    assert_eq!(rx.try_recv().unwrap().event, "alarm:fired");
+    // Inside the hysteresis band: still firing, no event.
+    engine.evaluate(&sample(46.0));
+    assert!(rx.try_recv().is_err());
+    assert!(engine.statuses()[0].firing);
+    engine.evaluate(&sample(44.0));
+    assert_eq!(rx.try_recv().unwrap().event, "alarm:cleared");
+    assert!(!engine.statuses()[0].firing);
+  }
+}
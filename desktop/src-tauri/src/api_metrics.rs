@@ -0,0 +1,132 @@
+//! Request, WebSocket, and database counters for the embedded API server.
+//! Rendered in the Prometheus text exposition format by `GET /metrics`.
+
+use std::{
+  collections::BTreeMap,
+  fmt::Write,
+  future::Future,
+  sync::{
+    atomic::{AtomicI64, AtomicU64, Ordering},
+    Mutex,
+  },
+  time::{Duration, Instant},
+};
+
+/// Upper bounds (seconds) of the DB latency histogram buckets.
+const DB_LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+#[derive(Default)]
+pub struct ApiMetrics {
+  requests_total: AtomicU64,
+  requests_by_status: Mutex<BTreeMap<u16, u64>>,
+  ws_active: AtomicI64,
+  ws_slow_disconnects: AtomicU64,
+  broadcast_sent: AtomicU64,
+  /// Non-cumulative per-bucket counts; the last slot is `+Inf`.
+  db_latency_buckets: [AtomicU64; DB_LATENCY_BUCKETS.len() + 1],
+  db_latency_count: AtomicU64,
+  db_latency_sum_us: AtomicU64,
+}
+
+/// Decrements the active WebSocket gauge when the connection task ends.
+pub struct WsConnectionGuard<'a>(&'a ApiMetrics);
+
+impl Drop for WsConnectionGuard<'_> {
+  fn drop(&mut self) {
+    self.0.ws_active.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+impl ApiMetrics {
+  pub fn record_request(&self, status: u16) {
+    self.requests_total.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut by_status) = self.requests_by_status.lock() {
+      *by_status.entry(status).or_default() += 1;
+    }
+  }
+
+  pub fn ws_connected(&self) -> WsConnectionGuard<'_> {
+    self.ws_active.fetch_add(1, Ordering::Relaxed);
+    WsConnectionGuard(self)
+  }
+
+  pub fn record_ws_slow_disconnect(&self) {
+    self.ws_slow_disconnects.fetch_add(1, Ordering::Relaxed);
+  }
+
+  pub fn record_broadcast(&self) {
+    self.broadcast_sent.fetch_add(1, Ordering::Relaxed);
+  }
+
+  pub fn record_db_latency(&self, elapsed: Duration) {
+    let seconds = elapsed.as_secs_f64();
+    let bucket = DB_LATENCY_BUCKETS
+      .iter()
+      .position(|bound| seconds <= *bound)
+      .unwrap_or(DB_LATENCY_BUCKETS.len());
+    self.db_latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    self.db_latency_count.fetch_add(1, Ordering::Relaxed);
+    self
+      .db_latency_sum_us
+      .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+  }
+
+  /// Awaits `query`, recording how long it took.
+  pub async fn time_db<T>(&self, query: impl Future<Output = T>) -> T {
+    let started = Instant::now();
+    let out = query.await;
+    self.record_db_latency(started.elapsed());
+    out
+  }
+
+  pub fn render(&self, pool_in_use: u32) -> String {
+    let mut out = String::new();
+    header(&mut out, "rs485_http_requests_total", "counter", "HTTP requests handled.");
+    let total = self.requests_total.load(Ordering::Relaxed);
+    let _ = writeln!(out, "rs485_http_requests_total {total}");
+
+    header(&mut out, "rs485_http_responses_total", "counter", "HTTP responses by status code.");
+    if let Ok(by_status) = self.requests_by_status.lock() {
+      for (status, count) in by_status.iter() {
+        let _ = writeln!(out, "rs485_http_responses_total{{status=\"{status}\"}} {count}");
+      }
+    }
+
+    header(&mut out, "rs485_ws_connections_active", "gauge", "Open realtime WebSockets.");
+    let active = self.ws_active.load(Ordering::Relaxed);
+    let _ = writeln!(out, "rs485_ws_connections_active {active}");
+
+    let name = "rs485_ws_slow_consumer_disconnects_total";
+    header(&mut out, name, "counter", "WebSockets closed for a full send queue.");
+    let slow = self.ws_slow_disconnects.load(Ordering::Relaxed);
+    let _ = writeln!(out, "{name} {slow}");
+
+    header(&mut out, "rs485_broadcast_events_total", "counter", "Events sent to realtime clients.");
+    let sent = self.broadcast_sent.load(Ordering::Relaxed);
+    let _ = writeln!(out, "rs485_broadcast_events_total {sent}");
+
+    let name = "rs485_db_query_duration_seconds";
+    header(&mut out, name, "histogram", "Database query latency.");
+    let mut cumulative = 0;
+    for (index, bucket) in self.db_latency_buckets.iter().enumerate() {
+      cumulative += bucket.load(Ordering::Relaxed);
+      let bound = DB_LATENCY_BUCKETS
+        .get(index)
+        .map_or_else(|| "+Inf".to_string(), f64::to_string);
+      let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}");
+    }
+    let sum_s = self.db_latency_sum_us.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+    let count = self.db_latency_count.load(Ordering::Relaxed);
+    let _ = writeln!(out, "{name}_sum {sum_s}");
+    let _ = writeln!(out, "{name}_count {count}");
+
+    header(&mut out, "rs485_db_pool_connections_in_use", "gauge", "Checked-out pool connections.");
+    let _ = writeln!(out, "rs485_db_pool_connections_in_use {pool_in_use}");
+    out
+  }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+  let _ = writeln!(out, "# HELP {name} {help}");
+  let _ = writeln!(out, "# TYPE {name} {kind}");
+}
@@ -2,27 +2,165 @@
 //! Provides spawn/kill utilities, a watchdog, and shared state for the backend child process.
 
 use std::{
-  net::TcpStream,
+  collections::VecDeque,
+  fs::{self, File, OpenOptions},
+  io::{BufRead, BufReader, Read, Write},
+  net::{TcpStream, ToSocketAddrs},
   process::{Child, Command, Stdio},
-  sync::{Arc, Mutex},
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, Mutex,
+  },
   thread,
-  time::Duration,
+  time::{Duration, Instant},
 };
 
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
+use crate::logs::default_log_path;
+
+/// Wait before the first watchdog restart; doubles per restart up to the cap.
+const RESTART_BACKOFF_INITIAL: Duration = Duration::from_secs(2);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// How long the backend must stay healthy before the backoff resets.
+const RESTART_BACKOFF_RESET: Duration = Duration::from_secs(60);
+
+/// Where the watchdog probes and how patient it is.
+/// Read from `RS485_BACKEND_*` / `RS485_WATCHDOG_*` by `from_env`.
+#[derive(Clone, Debug)]
+pub struct WatchdogConfig {
+  pub host: String,
+  pub port: u16,
+  pub interval_ms: u64,
+  /// Consecutive failed probes before a restart.
+  pub failure_threshold: u32,
+  /// Restarts allowed within `restart_window_ms` before giving up.
+  pub max_restarts: u32,
+  pub restart_window_ms: u64,
+  /// Also append backend output to the session log file.
+  pub log_to_file: bool,
+  /// Time allowed to exit after SIGTERM before it is killed (Unix).
+  pub terminate_grace_ms: u64,
+}
+
+impl Default for WatchdogConfig {
+  fn default() -> Self {
+    Self {
+      host: "127.0.0.1".to_string(),
+      port: 8000,
+      interval_ms: 2000,
+      failure_threshold: 3,
+      max_restarts: 5,
+      restart_window_ms: 10 * 60 * 1000,
+      log_to_file: false,
+      terminate_grace_ms: 5000,
+    }
+  }
+}
+
+impl WatchdogConfig {
+  pub fn from_env() -> Self {
+    fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+      std::env::var(name).ok()?.trim().parse().ok()
+    }
+    let defaults = Self::default();
+    Self {
+      host: var("RS485_BACKEND_HOST")
+        .or_else(|| var("HOST"))
+        .unwrap_or(defaults.host),
+      port: var("RS485_BACKEND_PORT")
+        .or_else(|| var("PORT"))
+        .unwrap_or(defaults.port),
+      interval_ms: var("RS485_WATCHDOG_INTERVAL_MS")
+        .unwrap_or(defaults.interval_ms)
+        .max(100),
+      failure_threshold: var("RS485_WATCHDOG_FAILURES")
+        .unwrap_or(defaults.failure_threshold)
+        .max(1),
+      max_restarts: var("RS485_WATCHDOG_MAX_RESTARTS").unwrap_or(defaults.max_restarts),
+      restart_window_ms: var("RS485_WATCHDOG_WINDOW_MS").unwrap_or(defaults.restart_window_ms),
+      log_to_file: var::<String>("RS485_BACKEND_LOG_TO_FILE").is_some_and(|value| value == "1"),
+      terminate_grace_ms: var("RS485_BACKEND_GRACE_MS").unwrap_or(defaults.terminate_grace_ms),
+    }
+  }
+
+  pub fn terminate_grace(&self) -> Duration {
+    Duration::from_millis(self.terminate_grace_ms)
+  }
+}
+
+/// `RS485_BACKEND=python` runs the FastAPI backend as a watched sidecar
+/// instead of the embedded Rust server.
+pub fn sidecar_enabled() -> bool {
+  std::env::var("RS485_BACKEND").is_ok_and(|value| value.eq_ignore_ascii_case("python"))
+}
+
+/// Payload of `backend:log`: one line of backend output.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendLogLine {
+  ts: String,
+  stream: &'static str,
+  line: String,
+}
+
+/// Payload of `backend:terminating` / `backend:terminated`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Termination {
+  pid: u32,
+  /// Set on `backend:terminated`: whether it exited before being killed.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  graceful: Option<bool>,
+}
+
+/// Payload of `backend:watchdog_restart`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchdogRestart {
+  /// How long the watchdog waits before respawning.
+  backoff_ms: u64,
+}
+
+/// Payload of `backend:gave_up`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GaveUp {
+  restarts: u32,
+  window_ms: u64,
+}
+
+/// Result of `backend_status`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendStatus {
+  /// `"sidecar"` for the watched Python backend, `"embedded"` for the Rust server.
+  pub mode: &'static str,
+  pub running: bool,
+  pub port_open: bool,
+  pub pid: Option<u32>,
+  /// Watchdog restarts since launch.
+  pub restarts: u32,
+}
+
 /// Shared state: backend child process handle.
 /// Using std::process::Child (stable) avoids plugin-shell private API issues.
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct BackendState {
   child: Arc<Mutex<Option<Child>>>,
+  restarts: Arc<AtomicU32>,
+  /// Consecutive failed watchdog probes; shared so a manual restart resets it.
+  failures: Arc<AtomicU32>,
 }
 
 impl BackendState {
-  pub fn new() -> Self {
-    Self {
-      child: Arc::new(Mutex::new(None)),
-    }
+  pub fn pid(&self) -> Option<u32> {
+    let guard = self.child.lock().expect("backend mutex poisoned");
+    guard.as_ref().map(Child::id)
+  }
+
+  pub fn restart_count(&self) -> u32 {
+    self.restarts.load(Ordering::Relaxed)
   }
 
   pub fn is_running(&self) -> bool {
@@ -45,11 +183,10 @@ impl BackendState {
 
 /// Cheap health probe: “is TCP port open?”
 fn backend_port_open(host: &str, port: u16) -> bool {
-  TcpStream::connect_timeout(
-    &format!("{host}:{port}").parse().unwrap(),
-    Duration::from_millis(150),
-  )
-  .is_ok()
+  let Some(addr) = (host, port).to_socket_addrs().ok().and_then(|mut addrs| addrs.next()) else {
+    return false;
+  };
+  TcpStream::connect_timeout(&addr, Duration::from_millis(150)).is_ok()
 }
 
 /// Spawn backend process (DEV default).
@@ -58,7 +195,11 @@ fn backend_port_open(host: &str, port: u16) -> bool {
 /// - For dev, the most reliable approach is running uvicorn via python.
 /// - For production installers, you’ll likely bundle a backend executable.
 ///   (I can give you the clean sidecar packaging next.)
-pub fn spawn_backend<R: Runtime>(app: &AppHandle<R>, state: &BackendState) -> anyhow::Result<()> {
+pub fn spawn_backend<R: Runtime>(
+  app: &AppHandle<R>,
+  state: &BackendState,
+  config: &WatchdogConfig,
+) -> anyhow::Result<()> {
   // If it’s already running, do nothing.
   if state.is_running() {
     let _ = app.emit("backend:already_running", ());
@@ -67,52 +208,156 @@ pub fn spawn_backend<R: Runtime>(app: &AppHandle<R>, state: &BackendState) -> an
 
   // DEV spawn (runs from repo)
   // desktop/ -> ../backend
+  let port = config.port.to_string();
   let mut cmd = Command::new("python");
   cmd.args([
     "-m",
     "uvicorn",
     "rs485_app.main:app",
     "--host",
-    "127.0.0.1",
+    &config.host,
     "--port",
-    "8000",
+    &port,
   ])
   .current_dir("../backend")
   .env("APP_ENV", "dev")
   .env("LOG_LEVEL", "INFO")
-  .env("HOST", "127.0.0.1")
-  .env("PORT", "8000")
+  .env("HOST", &config.host)
+  .env("PORT", &port)
   .stdout(Stdio::piped())
   .stderr(Stdio::piped());
 
-  let child = cmd.spawn()?;
+  let mut child = cmd.spawn()?;
+  // Drain both pipes so a chatty backend can't block on a full pipe.
+  let log_file = if config.log_to_file {
+    open_backend_log(app)
+  } else {
+    None
+  };
+  if let Some(stdout) = child.stdout.take() {
+    forward_output(app.clone(), "stdout", stdout, log_file.clone());
+  }
+  if let Some(stderr) = child.stderr.take() {
+    forward_output(app.clone(), "stderr", stderr, log_file);
+  }
   *state.child.lock().expect("backend mutex poisoned") = Some(child);
 
   let _ = app.emit("backend:spawned", ());
   Ok(())
 }
 
+fn open_backend_log<R: Runtime>(app: &AppHandle<R>) -> Option<Arc<Mutex<File>>> {
+  let opened = default_log_path(app).and_then(|path| {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&path)
+      .map_err(|err| format!("{}: {err}", path.display()))
+  });
+  match opened {
+    Ok(file) => Some(Arc::new(Mutex::new(file))),
+    Err(err) => {
+      eprintln!("[backend] not logging to file: {err}");
+      None
+    }
+  }
+}
+
+/// Emits each line of `pipe` as `backend:log`, optionally appending it to
+/// the session log, until the child closes the pipe.
+fn forward_output<R: Runtime>(
+  app: AppHandle<R>,
+  stream: &'static str,
+  pipe: impl Read + Send + 'static,
+  log_file: Option<Arc<Mutex<File>>>,
+) {
+  thread::spawn(move || {
+    let mut reader = BufReader::new(pipe);
+    let mut buf = Vec::new();
+    // read_until + lossy decoding keeps draining even on non-UTF-8 output.
+    while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+      let line = String::from_utf8_lossy(&buf).trim_end_matches(['\r', '\n']).to_string();
+      buf.clear();
+      let ts = chrono::Utc::now().to_rfc3339();
+      if let Some(file) = &log_file {
+        if let Ok(mut file) = file.lock() {
+          let _ = writeln!(file, "{ts} [backend {stream}] {line}");
+        }
+      }
+      let _ = app.emit("backend:log", BackendLogLine { ts, stream, line });
+    }
+  });
+}
+
 /// Kill backend if running (best effort).
-pub fn kill_backend(state: &BackendState) {
+pub fn kill_backend<R: Runtime>(app: &AppHandle<R>, state: &BackendState, grace: Duration) {
   let mut guard = state.child.lock().expect("backend mutex poisoned");
-  if let Some(mut child) = guard.take() {
+  let Some(mut child) = guard.take() else {
+    return;
+  };
+  let pid = child.id();
+  let _ = app.emit("backend:terminating", Termination { pid, graceful: None });
+  let graceful = terminate_gracefully(&mut child, grace);
+  if !graceful {
     let _ = child.kill();
-    let _ = child.wait();
   }
+  let _ = child.wait();
+  eprintln!("[backend] terminated pid={pid} graceful={graceful}");
+  let _ = app.emit(
+    "backend:terminated",
+    Termination {
+      pid,
+      graceful: Some(graceful),
+    },
+  );
+}
+
+/// Sends SIGTERM and waits up to `grace` for the child to exit, so the Python
+/// backend can close DB connections and flush. Returns whether it exited.
+#[cfg(unix)]
+fn terminate_gracefully(child: &mut Child, grace: Duration) -> bool {
+  let Ok(pid) = libc::pid_t::try_from(child.id()) else {
+    return false;
+  };
+  // SAFETY: plain syscall on our own child's pid, which isn't reaped yet.
+  if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+    return false;
+  }
+  let deadline = Instant::now() + grace;
+  loop {
+    match child.try_wait() {
+      Ok(Some(_)) => return true,
+      Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+      _ => return false,
+    }
+  }
+}
+
+/// Windows has no SIGTERM equivalent for console-less children; kill directly.
+#[cfg(not(unix))]
+fn terminate_gracefully(_child: &mut Child, _grace: Duration) -> bool {
+  false
 }
 
 /// Crash-safe watchdog:
 /// - If backend dies OR port stops responding, restart it.
-/// - Requires multiple consecutive failures to avoid flapping.
-pub fn start_watchdog<R: Runtime>(app: AppHandle<R>, state: BackendState) {
+/// - Requires `failure_threshold` consecutive failures to avoid flapping.
+/// - Waits with exponential backoff (2s, 4s, … 60s) before each restart so a
+///   crash-looping backend isn't hammered; resets after a stable period.
+/// - Gives up (emitting `backend:gave_up`) after `max_restarts` restarts
+///   within `restart_window_ms`.
+pub fn start_watchdog<R: Runtime>(app: AppHandle<R>, state: BackendState, config: WatchdogConfig) {
   thread::spawn(move || {
-    let host = "127.0.0.1";
-    let port = 8000u16;
-
-    let mut fails: u8 = 0;
+    let window = Duration::from_millis(config.restart_window_ms);
+    let mut restarts: VecDeque<Instant> = VecDeque::new();
+    let mut backoff = RESTART_BACKOFF_INITIAL;
+    let mut healthy_since: Option<Instant> = None;
 
     loop {
-      thread::sleep(Duration::from_secs(2));
+      thread::sleep(Duration::from_millis(config.interval_ms));
 
       // If the main window is gone, app is exiting — break.
       if app.get_webview_window("main").is_none() {
@@ -120,21 +365,99 @@ pub fn start_watchdog<R: Runtime>(app: AppHandle<R>, state: BackendState) {
       }
 
       // Probe health (port open)
-      if backend_port_open(host, port) {
-        fails = 0;
+      if backend_port_open(&config.host, config.port) {
+        state.failures.store(0, Ordering::Relaxed);
+        let since = *healthy_since.get_or_insert_with(Instant::now);
+        if since.elapsed() >= RESTART_BACKOFF_RESET {
+          backoff = RESTART_BACKOFF_INITIAL;
+        }
         continue;
       }
 
-      fails = fails.saturating_add(1);
+      healthy_since = None;
+      let fails = state.failures.fetch_add(1, Ordering::Relaxed).saturating_add(1);
       let _ = app.emit("backend:health_failed", fails);
 
-      // After 3 consecutive failures -> restart
-      if fails >= 3 {
-        kill_backend(&state);
-        let _ = app.emit("backend:watchdog_restart", ());
-        let _ = spawn_backend(&app, &state);
-        fails = 0;
+      if fails >= config.failure_threshold {
+        while restarts.front().is_some_and(|at| at.elapsed() > window) {
+          restarts.pop_front();
+        }
+        if restarts.len() >= config.max_restarts as usize {
+          eprintln!(
+            "[backend] watchdog giving up after {} restarts in {:?}",
+            restarts.len(),
+            window
+          );
+          let _ = app.emit(
+            "backend:gave_up",
+            GaveUp {
+              restarts: restarts.len() as u32,
+              window_ms: config.restart_window_ms,
+            },
+          );
+          break;
+        }
+        kill_backend(&app, &state, config.terminate_grace());
+        let _ = app.emit(
+          "backend:watchdog_restart",
+          WatchdogRestart {
+            backoff_ms: backoff.as_millis() as u64,
+          },
+        );
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+        let _ = spawn_backend(&app, &state, &config);
+        state.restarts.fetch_add(1, Ordering::Relaxed);
+        restarts.push_back(Instant::now());
+        state.failures.store(0, Ordering::Relaxed);
       }
     }
   });
 }
+
+/// Kills and respawns the sidecar on demand (menu item or command) and
+/// resets the watchdog's failure count. Emits `backend:restarted`.
+pub fn restart_sidecar<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let (Some(state), Some(config)) =
+    (app.try_state::<BackendState>(), app.try_state::<WatchdogConfig>())
+  else {
+    return Err("The backend is embedded in the app; there is no sidecar to restart".to_string());
+  };
+  kill_backend(app, &state, config.terminate_grace());
+  state.failures.store(0, Ordering::Relaxed);
+  spawn_backend(app, &state, &config).map_err(|err| format!("{err:#}"))?;
+  eprintln!("[backend] restarted on request pid={:?}", state.pid());
+  let _ = app.emit("backend:restarted", state.pid());
+  Ok(())
+}
+
+#[tauri::command]
+pub fn restart_backend<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+  restart_sidecar(&app)
+}
+
+/// Current backend health, for UIs that missed the push events.
+#[tauri::command]
+pub fn backend_status<R: Runtime>(app: AppHandle<R>) -> BackendStatus {
+  let config = app
+    .try_state::<WatchdogConfig>()
+    .map_or_else(WatchdogConfig::from_env, |config| config.inner().clone());
+  let port_open = backend_port_open(&config.host, config.port);
+  match app.try_state::<BackendState>() {
+    Some(state) => BackendStatus {
+      mode: "sidecar",
+      running: state.is_running(),
+      port_open,
+      pid: state.pid(),
+      restarts: state.restart_count(),
+    },
+    // The embedded server lives in this process.
+    None => BackendStatus {
+      mode: "embedded",
+      running: port_open,
+      port_open,
+      pid: Some(std::process::id()),
+      restarts: 0,
+    },
+  }
+}
@@ -0,0 +1,139 @@
+//! Checksum strategies for custom serial protocols.
+//! Computes and verifies XOR, CRC8, Modbus CRC16, and Modbus ASCII LRC trailers.
+
+use crate::modbus::{crc16, lrc};
+use crate::serial::{bytes_to_hex, encode_payload, hex_to_bytes, SerialError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Checksum {
+  None,
+  /// XOR of all bytes (1 byte).
+  Xor,
+  /// CRC-8, polynomial 0x07, initial value 0x00 (1 byte).
+  Crc8,
+  /// Modbus RTU CRC16, low byte first (2 bytes).
+  Crc16Modbus,
+  /// Modbus ASCII LRC. Input is the ASCII-hex message body; the trailer is
+  /// two uppercase ASCII hex characters.
+  LrcAscii,
+}
+
+impl Checksum {
+  /// Trailer bytes for `data`.
+  pub fn compute(self, data: &[u8]) -> Result<Vec<u8>, SerialError> {
+    Ok(match self {
+      Checksum::None => Vec::new(),
+      Checksum::Xor => vec![data.iter().fold(0, |acc, b| acc ^ b)],
+      Checksum::Crc8 => vec![crc8(data)],
+      Checksum::Crc16Modbus => crc16(data).to_le_bytes().to_vec(),
+      Checksum::LrcAscii => {
+        let text = std::str::from_utf8(data)
+          .map_err(|_| SerialError::InvalidInput("LRC input must be ASCII hex".to_string()))?;
+        format!("{:02X}", lrc(&hex_to_bytes(text)?)).into_bytes()
+      }
+    })
+  }
+
+  fn len(self) -> usize {
+    match self {
+      Checksum::None => 0,
+      Checksum::Xor | Checksum::Crc8 => 1,
+      Checksum::Crc16Modbus | Checksum::LrcAscii => 2,
+    }
+  }
+
+  /// Checks that `frame` ends with the checksum of everything before it.
+  pub fn verify(self, frame: &[u8]) -> Result<bool, SerialError> {
+    let Some(split) = frame.len().checked_sub(self.len()) else {
+      return Ok(false);
+    };
+    let (body, trailer) = frame.split_at(split);
+    let expected = self.compute(body)?;
+    Ok(match self {
+      // LRC trailers are hex text, so accept lowercase digits too.
+      Checksum::LrcAscii => expected.eq_ignore_ascii_case(trailer),
+      _ => expected == trailer,
+    })
+  }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+  let mut crc: u8 = 0;
+  for &byte in data {
+    crc ^= byte;
+    for _ in 0..8 {
+      crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+    }
+  }
+  crc
+}
+
+/// Returns `data` with the `algo` trailer appended, as hex.
+#[tauri::command]
+pub fn serial_append_checksum(
+  data: String,
+  format: Option<String>,
+  algo: Checksum,
+) -> Result<String, SerialError> {
+  let mut bytes = encode_payload(data, format.as_deref(), None)?;
+  let trailer = algo.compute(&bytes)?;
+  bytes.extend_from_slice(&trailer);
+  Ok(bytes_to_hex(&bytes))
+}
+
+#[tauri::command]
+pub fn serial_verify_checksum(
+  frame: String,
+  format: Option<String>,
+  algo: Checksum,
+) -> Result<bool, SerialError> {
+  algo.verify(&encode_payload(frame, format.as_deref(), None)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CHECK: &[u8] = b"123456789";
+
+  #[test]
+  fn xor_known_vector() {
+    assert_eq!(Checksum::Xor.compute(CHECK).unwrap(), [0x31]);
+  }
+
+  #[test]
+  fn crc8_known_vector() {
+    assert_eq!(Checksum::Crc8.compute(CHECK).unwrap(), [0xF4]);
+  }
+
+  #[test]
+  fn crc16_modbus_known_vector() {
+    assert_eq!(Checksum::Crc16Modbus.compute(CHECK).unwrap(), [0x37, 0x4B]);
+  }
+
+  #[test]
+  fn lrc_ascii_known_vector() {
+    assert_eq!(Checksum::LrcAscii.compute(b"010300000001").unwrap(), b"FB");
+    assert!(Checksum::LrcAscii.verify(b"010300000001fb").unwrap());
+  }
+
+  #[test]
+  fn verify_detects_corruption() {
+    for algo in [Checksum::Xor, Checksum::Crc8, Checksum::Crc16Modbus] {
+      let mut frame = CHECK.to_vec();
+      frame.extend(algo.compute(CHECK).unwrap());
+      assert!(algo.verify(&frame).unwrap(), "{algo:?}");
+      frame[0] ^= 0x01;
+      assert!(!algo.verify(&frame).unwrap(), "{algo:?}");
+    }
+    assert!(Checksum::None.verify(CHECK).unwrap());
+  }
+
+  #[test]
+  fn binary_trailers_are_case_sensitive() {
+    // XOR of 0x41 is 0x41 ('A'); 0x61 ('a') must not pass.
+    assert!(Checksum::Xor.verify(&[0x41, 0x41]).unwrap());
+    assert!(!Checksum::Xor.verify(&[0x41, 0x61]).unwrap());
+  }
+}
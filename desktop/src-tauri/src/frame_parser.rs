@@ -0,0 +1,492 @@
+//! Rule-based decoding of serial frames into telemetry metrics.
+//! A JSON `FrameParser` lists fixed-offset fields or a regex; no device-specific code needed.
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  sync::OnceLock,
+};
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::serial::{encode_payload, hex_to_bytes, SerialError};
+
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+  U16,
+  I16,
+  F32,
+  Ascii,
+}
+
+impl FieldType {
+  /// Fixed byte width; `None` for `ascii`, which takes its length from the field.
+  fn width(self) -> Option<usize> {
+    match self {
+      FieldType::U16 | FieldType::I16 => Some(2),
+      FieldType::F32 => Some(4),
+      FieldType::Ascii => None,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endian {
+  #[default]
+  Big,
+  Little,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameField {
+  pub name: String,
+  pub offset: usize,
+  /// Byte count; defaults to the type's width and is required for `ascii`.
+  #[serde(default)]
+  pub len: Option<usize>,
+  #[serde(rename = "type")]
+  pub kind: FieldType,
+  #[serde(default)]
+  pub endian: Endian,
+  /// Multiplier applied to numeric fields.
+  #[serde(default)]
+  pub scale: Option<f64>,
+}
+
+impl FrameField {
+  fn byte_len(&self) -> usize {
+    self.len.or(self.kind.width()).unwrap_or(0)
+  }
+
+  fn decode(&self, bytes: &[u8]) -> Value {
+    // Unscaled integers stay integers; any scale makes the value a float.
+    let number = |raw: f64, unscaled: Value| match self.scale {
+      Some(scale) => Value::from(raw * scale),
+      None => unscaled,
+    };
+    match self.kind {
+      FieldType::U16 => {
+        let raw = [bytes[0], bytes[1]];
+        let value = match self.endian {
+          Endian::Big => u16::from_be_bytes(raw),
+          Endian::Little => u16::from_le_bytes(raw),
+        };
+        number(f64::from(value), Value::from(value))
+      }
+      FieldType::I16 => {
+        let raw = [bytes[0], bytes[1]];
+        let value = match self.endian {
+          Endian::Big => i16::from_be_bytes(raw),
+          Endian::Little => i16::from_le_bytes(raw),
+        };
+        number(f64::from(value), Value::from(value))
+      }
+      FieldType::F32 => {
+        let raw = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let value = match self.endian {
+          Endian::Big => f32::from_be_bytes(raw),
+          Endian::Little => f32::from_le_bytes(raw),
+        };
+        // NaN/inf have no JSON form and come out as null.
+        number(f64::from(value), Value::from(f64::from(value)))
+      }
+      FieldType::Ascii => {
+        let text = String::from_utf8_lossy(bytes);
+        Value::from(text.trim_matches(|c: char| c == '\0' || c.is_whitespace()))
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameMode {
+  /// Fixed-offset `fields`.
+  #[default]
+  Binary,
+  /// Named capture groups of `pattern` over the frame as text.
+  Regex,
+}
+
+/// Conversion applied to a regex capture; uncoerced captures stay text.
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Coercion {
+  Number,
+  Integer,
+}
+
+/// Layout of one device frame.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameParser {
+  #[serde(default)]
+  pub mode: FrameMode,
+  /// Hex bytes that end a frame on the wire, e.g. `"0D 0A"`. Regex mode
+  /// defaults to a newline.
+  #[serde(default)]
+  pub delimiter: Option<String>,
+  /// Expected frame length (without the delimiter); fields must fit inside it.
+  #[serde(default)]
+  pub length: Option<usize>,
+  #[serde(default)]
+  pub fields: Vec<FrameField>,
+  /// Regex mode: each named group becomes a metric.
+  #[serde(default)]
+  pub pattern: Option<String>,
+  /// Regex mode: per-capture coercion, keyed by group name.
+  #[serde(default)]
+  pub coerce: BTreeMap<String, Coercion>,
+  /// `pattern`, compiled on first use.
+  #[serde(skip)]
+  compiled: OnceLock<Regex>,
+}
+
+/// Result of `FrameParser::parse`, shaped like a telemetry event body.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ParsedFrame {
+  pub metrics: Value,
+  pub quality: Value,
+}
+
+impl FrameParser {
+  /// Decoded `delimiter`; empty when frames aren't delimited.
+  pub fn delimiter_bytes(&self) -> Result<Vec<u8>, SerialError> {
+    match self.delimiter.as_deref().map(str::trim) {
+      None | Some("") if self.mode == FrameMode::Regex => Ok(b"\n".to_vec()),
+      None | Some("") => Ok(Vec::new()),
+      Some(hex) => hex_to_bytes(hex),
+    }
+  }
+
+  fn regex(&self) -> Result<&Regex, SerialError> {
+    if let Some(regex) = self.compiled.get() {
+      return Ok(regex);
+    }
+    let pattern = self
+      .pattern
+      .as_deref()
+      .ok_or_else(|| SerialError::InvalidInput("Regex parser needs a pattern".to_string()))?;
+    let regex = Regex::new(pattern)
+      .map_err(|err| SerialError::InvalidInput(format!("Invalid pattern: {err}")))?;
+    Ok(self.compiled.get_or_init(|| regex))
+  }
+
+  /// Rejects configs that could never decode: bad types/lengths, duplicate
+  /// names, fields reaching past `length`, or a pattern without named groups.
+  pub fn validate(&self) -> Result<(), SerialError> {
+    self.delimiter_bytes()?;
+    match self.mode {
+      FrameMode::Binary => self.validate_fields(),
+      FrameMode::Regex => self.validate_pattern(),
+    }
+  }
+
+  fn validate_pattern(&self) -> Result<(), SerialError> {
+    let regex = self.regex()?;
+    let names: BTreeSet<&str> = regex.capture_names().flatten().collect();
+    if names.is_empty() {
+      return Err(SerialError::InvalidInput(
+        "Pattern has no named groups, e.g. (?P<volts>[0-9.]+)".to_string(),
+      ));
+    }
+    if let Some(name) = self.coerce.keys().find(|name| !names.contains(name.as_str())) {
+      return Err(SerialError::InvalidInput(format!(
+        "Coercion for \"{name}\", which isn't a group in the pattern"
+      )));
+    }
+    Ok(())
+  }
+
+  fn validate_fields(&self) -> Result<(), SerialError> {
+    let invalid = |msg: String| Err(SerialError::InvalidInput(msg));
+    if self.fields.is_empty() {
+      return invalid("Frame parser has no fields".to_string());
+    }
+    let mut names = BTreeSet::new();
+    for field in &self.fields {
+      let name = &field.name;
+      if name.trim().is_empty() {
+        return invalid("Frame field name must not be empty".to_string());
+      }
+      if !names.insert(name.as_str()) {
+        return invalid(format!("Duplicate frame field \"{name}\""));
+      }
+      match (field.kind.width(), field.len) {
+        (None, None | Some(0)) => {
+          return invalid(format!("Field \"{name}\": ascii fields need a len"));
+        }
+        (Some(width), Some(len)) if len != width => {
+          let kind = field.kind;
+          return invalid(format!("Field \"{name}\": {kind:?} is {width} bytes, not {len}"));
+        }
+        _ => {}
+      }
+      match (field.kind, field.scale) {
+        (FieldType::Ascii, Some(_)) => {
+          return invalid(format!("Field \"{name}\": scale only applies to numbers"));
+        }
+        (_, Some(scale)) if !scale.is_finite() => {
+          return invalid(format!("Field \"{name}\": scale must be finite"));
+        }
+        _ => {}
+      }
+      let Some(end) = field.offset.checked_add(field.byte_len()) else {
+        return invalid(format!("Field \"{name}\": offset out of range"));
+      };
+      if let Some(length) = self.length.filter(|length| end > *length) {
+        return invalid(format!(
+          "Field \"{name}\" ends at byte {end}, past the {length}-byte frame"
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  /// Decodes `frame`; call `validate` first.
+  pub fn parse(&self, frame: &[u8]) -> ParsedFrame {
+    match self.mode {
+      FrameMode::Binary => self.parse_fields(frame),
+      FrameMode::Regex => self.parse_text(frame),
+    }
+  }
+
+  /// A non-matching line yields empty metrics and `quality.matched: false`.
+  /// Captures that fail coercion stay text and are listed in `quality.uncoerced`.
+  fn parse_text(&self, frame: &[u8]) -> ParsedFrame {
+    let text = String::from_utf8_lossy(frame);
+    let text = text.trim_end_matches(['\r', '\n']);
+    let mut metrics = Map::new();
+    let mut uncoerced = Vec::new();
+    let captures = self.regex().ok().and_then(|regex| Some((regex, regex.captures(text)?)));
+    if let Some((regex, captures)) = &captures {
+      for name in regex.capture_names().flatten() {
+        let Some(capture) = captures.name(name) else {
+          continue;
+        };
+        let raw = capture.as_str().trim();
+        let value = match self.coerce.get(name) {
+          None => Some(Value::from(raw)),
+          Some(Coercion::Integer) => raw.parse::<i64>().ok().map(Value::from),
+          Some(Coercion::Number) => raw
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(Value::from),
+        };
+        let value = value.unwrap_or_else(|| {
+          uncoerced.push(name.to_string());
+          Value::from(raw)
+        });
+        metrics.insert(name.to_string(), value);
+      }
+    }
+    let mut quality = json!({ "matched": captures.is_some() });
+    if !uncoerced.is_empty() {
+      quality["uncoerced"] = Value::from(uncoerced);
+    }
+    ParsedFrame {
+      metrics: Value::Object(metrics),
+      quality,
+    }
+  }
+
+  /// Decodes every field that fits in `frame`. Fields past the end of a short
+  /// frame are left out and listed in `quality.missing_fields`.
+  fn parse_fields(&self, frame: &[u8]) -> ParsedFrame {
+    let mut metrics = Map::new();
+    let mut missing = Vec::new();
+    for field in &self.fields {
+      match frame.get(field.offset..field.offset + field.byte_len()) {
+        Some(bytes) => {
+          metrics.insert(field.name.clone(), field.decode(bytes));
+        }
+        None => missing.push(field.name.clone()),
+      }
+    }
+    let mut quality = json!({
+      "frame_len": frame.len(),
+      "short_frame": !missing.is_empty() || self.length.is_some_and(|len| frame.len() < len),
+    });
+    if !missing.is_empty() {
+      quality["missing_fields"] = Value::from(missing);
+    }
+    ParsedFrame {
+      metrics: Value::Object(metrics),
+      quality,
+    }
+  }
+}
+
+/// Decodes one frame with `parser`, returning `{ metrics, quality }`.
+/// A trailing delimiter, if present, is ignored.
+#[tauri::command]
+pub fn parse_serial_frame(
+  frame: String,
+  format: Option<String>,
+  parser: FrameParser,
+) -> Result<Value, SerialError> {
+  parser.validate()?;
+  let bytes = encode_payload(frame, format.as_deref(), None)?;
+  let delimiter = parser.delimiter_bytes()?;
+  let body = match bytes.strip_suffix(delimiter.as_slice()) {
+    Some(body) if !delimiter.is_empty() => body,
+    _ => &bytes,
+  };
+  serde_json::to_value(parser.parse(body))
+    .map_err(|err| SerialError::Io(format!("Failed to encode frame: {err}")))
+}
+
+/// Validates `parser` and runs it over every frame in `sample` (split on the
+/// parser's delimiter, so several example lines can be checked at once).
+#[tauri::command]
+pub fn test_frame_parser(
+  sample: String,
+  format: Option<String>,
+  parser: FrameParser,
+) -> Result<Vec<ParsedFrame>, SerialError> {
+  parser.validate()?;
+  let bytes = encode_payload(sample, format.as_deref(), None)?;
+  let delimiter = parser.delimiter_bytes()?;
+  let frames: Vec<&[u8]> = if delimiter.is_empty() {
+    vec![&bytes]
+  } else {
+    split_frames(&bytes, &delimiter)
+  };
+  Ok(
+    frames
+      .into_iter()
+      .filter(|frame| !frame.is_empty())
+      .map(|frame| parser.parse(frame))
+      .collect(),
+  )
+}
+
+fn split_frames<'a>(mut bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+  let mut frames = Vec::new();
+  while let Some(end) = bytes.windows(delimiter.len()).position(|window| window == delimiter) {
+    frames.push(&bytes[..end]);
+    bytes = &bytes[end + delimiter.len()..];
+  }
+  frames.push(bytes);
+  frames
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parser(config: Value) -> FrameParser {
+    serde_json::from_value(config).unwrap()
+  }
+
+  fn binary_parser() -> FrameParser {
+    parser(json!({
+      "length": 20,
+      "fields": [
+        { "name": "u16_be", "offset": 0, "type": "u16", "scale": 0.5 },
+        { "name": "u16_le", "offset": 2, "type": "u16", "endian": "little" },
+        { "name": "i16_be", "offset": 4, "type": "i16", "scale": 0.5 },
+        { "name": "i16_le", "offset": 6, "type": "i16", "endian": "little" },
+        { "name": "f32_be", "offset": 8, "type": "f32" },
+        { "name": "f32_le", "offset": 12, "type": "f32", "endian": "little", "scale": 2.0 },
+        { "name": "label", "offset": 16, "len": 4, "type": "ascii" },
+      ],
+    }))
+  }
+
+  const FRAME: [u8; 20] = [
+    0x01, 0x2C, 0x2C, 0x01, 0xFF, 0x9C, 0x9C, 0xFF, 0x41, 0x48, 0x00, 0x00, 0x00, 0x00, 0x48,
+    0x41, b'A', b'B', 0, 0,
+  ];
+
+  #[test]
+  fn decodes_each_type_and_endianness_with_scale() {
+    let parser = binary_parser();
+    parser.validate().unwrap();
+    let parsed = parser.parse(&FRAME);
+    assert_eq!(
+      parsed.metrics,
+      json!({
+        "u16_be": 150.0,
+        "u16_le": 300,
+        "i16_be": -50.0,
+        "i16_le": -100,
+        "f32_be": 12.5,
+        "f32_le": 25.0,
+        "label": "AB",
+      })
+    );
+    assert_eq!(parsed.quality, json!({ "frame_len": 20, "short_frame": false }));
+  }
+
+  #[test]
+  fn rejects_fields_past_the_frame_length() {
+    let parser = parser(json!({
+      "length": 4,
+      "fields": [{ "name": "temp", "offset": 2, "type": "f32" }],
+    }));
+    let err = parser.validate().unwrap_err();
+    assert!(err.to_string().contains("past the 4-byte frame"), "{err}");
+  }
+
+  #[test]
+  fn short_frame_keeps_the_fields_that_fit() {
+    let parsed = binary_parser().parse(&FRAME[..10]);
+    assert_eq!(
+      parsed.metrics,
+      json!({ "u16_be": 150.0, "u16_le": 300, "i16_be": -50.0, "i16_le": -100 })
+    );
+    assert_eq!(
+      parsed.quality,
+      json!({
+        "frame_len": 10,
+        "short_frame": true,
+        "missing_fields": ["f32_be", "f32_le", "label"],
+      })
+    );
+  }
+
+  fn regex_parser(t_group: &str) -> FrameParser {
+    parser(json!({
+      "mode": "regex",
+      "pattern": format!("V=(?P<V>[0-9.]+),I=(?P<I>[0-9.]+),T=(?P<T>{t_group})"),
+      "coerce": { "V": "number", "I": "number", "T": "integer" },
+    }))
+  }
+
+  #[test]
+  fn regex_captures_are_coerced() {
+    let parser = regex_parser("-?[0-9]+");
+    parser.validate().unwrap();
+    let parsed = parser.parse(b"V=12.3,I=0.45,T=25\r\n");
+    assert_eq!(parsed.metrics, json!({ "V": 12.3, "I": 0.45, "T": 25 }));
+    assert_eq!(parsed.quality, json!({ "matched": true }));
+
+    let parsed = parser.parse(b"garbage");
+    assert_eq!(parsed.metrics, json!({}));
+    assert_eq!(parsed.quality, json!({ "matched": false }));
+  }
+
+  #[test]
+  fn failed_coercion_keeps_the_text() {
+    let parsed = regex_parser("[^,]+").parse(b"V=12.3,I=0.45,T=hot");
+    assert_eq!(parsed.metrics, json!({ "V": 12.3, "I": 0.45, "T": "hot" }));
+    assert_eq!(parsed.quality, json!({ "matched": true, "uncoerced": ["T"] }));
+  }
+
+  #[test]
+  fn test_frame_parser_splits_sample_lines() {
+    let sample = "V=1,I=2,T=3\r\nnoise\nV=4,I=5,T=6\n".to_string();
+    let frames = test_frame_parser(sample, None, regex_parser("-?[0-9]+")).unwrap();
+    let metrics: Vec<_> = frames.iter().map(|frame| frame.metrics.clone()).collect();
+    assert_eq!(
+      metrics,
+      [json!({ "V": 1.0, "I": 2.0, "T": 3 }), json!({}), json!({ "V": 4.0, "I": 5.0, "T": 6 })]
+    );
+    assert_eq!(frames[1].quality, json!({ "matched": false }));
+  }
+}
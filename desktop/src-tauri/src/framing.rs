@@ -0,0 +1,212 @@
+//! Byte-stuffing framers for binary packets on the bus.
+//! Encodes and decodes COBS and SLIP frames; commands return hex like the serial reads.
+
+use crate::serial::{bytes_to_hex, encode_payload, SerialError};
+
+/// Packet delimiter on COBS links.
+const COBS_DELIMITER: u8 = 0x00;
+/// Largest code byte: 254 data bytes with no implied zero.
+const COBS_MAX_CODE: u8 = 0xFF;
+
+/// SLIP special bytes (RFC 1055).
+const SLIP_END: u8 = 0xC0;
+const SLIP_ESC: u8 = 0xDB;
+const SLIP_ESC_END: u8 = 0xDC;
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+/// Stuffs `data` so it contains no zeros and appends the `0x00` delimiter.
+fn cobs_encode_bytes(data: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(data.len() + data.len() / 254 + 2);
+  let mut code_at = 0;
+  out.push(0);
+  let mut code: u8 = 1;
+  for &byte in data {
+    if byte == COBS_DELIMITER {
+      out[code_at] = code;
+      code_at = out.len();
+      out.push(0);
+      code = 1;
+      continue;
+    }
+    out.push(byte);
+    code += 1;
+    if code == COBS_MAX_CODE {
+      out[code_at] = code;
+      code_at = out.len();
+      out.push(0);
+      code = 1;
+    }
+  }
+  out[code_at] = code;
+  out.push(COBS_DELIMITER);
+  out
+}
+
+/// Reverses `cobs_encode_bytes`. The trailing delimiter is optional; a zero
+/// anywhere else, or a code running past the end, is rejected.
+fn cobs_decode_bytes(frame: &[u8]) -> Result<Vec<u8>, SerialError> {
+  let frame = frame.strip_suffix(&[COBS_DELIMITER]).unwrap_or(frame);
+  let mut out = Vec::with_capacity(frame.len());
+  let mut at = 0;
+  while at < frame.len() {
+    let code = frame[at];
+    if code == COBS_DELIMITER {
+      return Err(SerialError::InvalidInput(format!("Unexpected COBS delimiter at byte {at}")));
+    }
+    let end = at + usize::from(code);
+    if end > frame.len() {
+      return Err(SerialError::InvalidInput(format!(
+        "COBS code at byte {at} points past the end of the frame"
+      )));
+    }
+    let block = &frame[at + 1..end];
+    if block.contains(&COBS_DELIMITER) {
+      return Err(SerialError::InvalidInput("Unexpected COBS delimiter in block".to_string()));
+    }
+    out.extend_from_slice(block);
+    at = end;
+    // A short block implies a zero, except after the final block.
+    if code != COBS_MAX_CODE && at < frame.len() {
+      out.push(0);
+    }
+  }
+  Ok(out)
+}
+
+/// COBS-encodes `data` (text, hex, or base64 per `format`) including the
+/// trailing `00` delimiter, and returns the frame as hex.
+#[tauri::command]
+pub fn cobs_encode(data: String, format: Option<String>) -> Result<String, SerialError> {
+  let bytes = encode_payload(data, format.as_deref(), None)?;
+  Ok(bytes_to_hex(&cobs_encode_bytes(&bytes)))
+}
+
+#[tauri::command]
+pub fn cobs_decode(frame: String, format: Option<String>) -> Result<String, SerialError> {
+  let bytes = encode_payload(frame, format.as_deref(), None)?;
+  Ok(bytes_to_hex(&cobs_decode_bytes(&bytes)?))
+}
+
+/// Escapes END/ESC in `data` and wraps it in END bytes. The leading END
+/// flushes any line noise the receiver accumulated before the packet.
+fn slip_encode_bytes(data: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(data.len() + 2);
+  out.push(SLIP_END);
+  for &byte in data {
+    match byte {
+      SLIP_END => out.extend_from_slice(&[SLIP_ESC, SLIP_ESC_END]),
+      SLIP_ESC => out.extend_from_slice(&[SLIP_ESC, SLIP_ESC_ESC]),
+      _ => out.push(byte),
+    }
+  }
+  out.push(SLIP_END);
+  out
+}
+
+/// Strips framing END bytes and undoes escaping. END bytes inside the frame
+/// are dropped as empty packet boundaries.
+fn slip_decode_bytes(frame: &[u8]) -> Result<Vec<u8>, SerialError> {
+  let mut out = Vec::with_capacity(frame.len());
+  let mut bytes = frame.iter().enumerate();
+  while let Some((at, &byte)) = bytes.next() {
+    match byte {
+      SLIP_END => {}
+      SLIP_ESC => match bytes.next() {
+        Some((_, &SLIP_ESC_END)) => out.push(SLIP_END),
+        Some((_, &SLIP_ESC_ESC)) => out.push(SLIP_ESC),
+        Some((_, &other)) => {
+          return Err(SerialError::InvalidInput(format!(
+            "Invalid SLIP escape 0x{other:02X} at byte {}",
+            at + 1
+          )))
+        }
+        None => {
+          return Err(SerialError::InvalidInput(
+            "Dangling SLIP escape at end of frame".to_string(),
+          ))
+        }
+      },
+      _ => out.push(byte),
+    }
+  }
+  Ok(out)
+}
+
+/// SLIP-encodes `data` with leading and trailing END bytes; returns hex.
+#[tauri::command]
+pub fn slip_encode(data: String, format: Option<String>) -> Result<String, SerialError> {
+  let bytes = encode_payload(data, format.as_deref(), None)?;
+  Ok(bytes_to_hex(&slip_encode_bytes(&bytes)))
+}
+
+#[tauri::command]
+pub fn slip_decode(frame: String, format: Option<String>) -> Result<String, SerialError> {
+  let bytes = encode_payload(frame, format.as_deref(), None)?;
+  Ok(bytes_to_hex(&slip_decode_bytes(&bytes)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn round_trip(data: &[u8]) -> Vec<u8> {
+    let encoded = cobs_encode_bytes(data);
+    assert_eq!(encoded.last(), Some(&COBS_DELIMITER));
+    assert!(!encoded[..encoded.len() - 1].contains(&COBS_DELIMITER));
+    assert_eq!(cobs_decode_bytes(&encoded).unwrap(), data);
+    encoded
+  }
+
+  #[test]
+  fn cobs_known_vectors() {
+    assert_eq!(round_trip(&[]), [0x01, 0x00]);
+    assert_eq!(round_trip(&[0x00]), [0x01, 0x01, 0x00]);
+    assert_eq!(round_trip(&[0x00, 0x00]), [0x01, 0x01, 0x01, 0x00]);
+    assert_eq!(round_trip(&[0x11, 0x22, 0x00, 0x33]), [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+  }
+
+  #[test]
+  fn cobs_long_non_zero_runs() {
+    let run: Vec<u8> = (1..=254).collect();
+    let encoded = round_trip(&run);
+    assert_eq!(encoded[0], 0xFF);
+    assert_eq!(encoded.len(), 257);
+
+    let longer: Vec<u8> = (0..600).map(|i| (i % 255 + 1) as u8).collect();
+    round_trip(&longer);
+    let mut with_zero = run.clone();
+    with_zero.push(0);
+    round_trip(&with_zero);
+  }
+
+  #[test]
+  fn cobs_rejects_malformed_frames() {
+    assert!(cobs_decode_bytes(&[0x05, 0x11, 0x22]).is_err());
+    assert!(cobs_decode_bytes(&[0x03, 0x11, 0x00, 0x01]).is_err());
+    assert!(cobs_decode_bytes(&[0x00, 0x01]).is_err());
+  }
+
+  #[test]
+  fn slip_escapes_end_and_esc() {
+    let data = [0x01, SLIP_END, 0x02, SLIP_ESC, 0x03];
+    let encoded = slip_encode_bytes(&data);
+    assert_eq!(
+      encoded,
+      [SLIP_END, 0x01, SLIP_ESC, SLIP_ESC_END, 0x02, SLIP_ESC, SLIP_ESC_ESC, 0x03, SLIP_END]
+    );
+    assert_eq!(slip_decode_bytes(&encoded).unwrap(), data);
+  }
+
+  #[test]
+  fn slip_decodes_raw_escape_sequences() {
+    let frame = [SLIP_ESC, SLIP_ESC_ESC, SLIP_ESC, SLIP_ESC_END, SLIP_END];
+    assert_eq!(slip_decode_bytes(&frame).unwrap(), [SLIP_ESC, SLIP_END]);
+    assert!(slip_decode_bytes(&slip_encode_bytes(&[])).unwrap().is_empty());
+  }
+
+  #[test]
+  fn slip_rejects_bad_escapes() {
+    assert!(slip_decode_bytes(&[0x01, SLIP_ESC]).is_err());
+    assert!(slip_decode_bytes(&[SLIP_ESC, 0x01, SLIP_END]).is_err());
+  }
+}
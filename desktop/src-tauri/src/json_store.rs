@@ -0,0 +1,91 @@
+//! Small JSON files in the app config dir: serial presets, port aliases, recent ports.
+//! Reads treat a missing or corrupt file as empty; writes go through a temp file.
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::{Mutex, MutexGuard},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::serial::SerialError;
+
+const MAX_NAME_LEN: usize = 64;
+
+/// One config file plus the lock that serializes read-modify-write cycles on it.
+pub struct JsonStore {
+  file: &'static str,
+  /// What the file holds, for log and error messages.
+  label: &'static str,
+  lock: Mutex<()>,
+}
+
+impl JsonStore {
+  pub const fn new(file: &'static str, label: &'static str) -> Self {
+    JsonStore {
+      file,
+      label,
+      lock: Mutex::new(()),
+    }
+  }
+
+  pub fn path<R: Runtime>(&self, app: &AppHandle<R>) -> Result<PathBuf, SerialError> {
+    let dir = app
+      .path()
+      .app_config_dir()
+      .map_err(|err| SerialError::Io(format!("App config dir unavailable: {err}")))?;
+    Ok(dir.join(self.file))
+  }
+
+  pub fn lock(&self) -> Result<MutexGuard<'_, ()>, SerialError> {
+    Ok(self.lock.lock()?)
+  }
+
+  /// A missing or unreadable file yields `T::default()`.
+  pub fn read<T: DeserializeOwned + Default>(&self, path: &Path) -> T {
+    let Ok(data) = fs::read(path) else {
+      return T::default();
+    };
+    serde_json::from_slice(&data).unwrap_or_else(|err| {
+      eprintln!("[serial] ignoring corrupt {} file {}: {err}", self.label, path.display());
+      T::default()
+    })
+  }
+
+  /// Writes via a temp file so a crash mid-write can't corrupt the file.
+  pub fn write<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<(), SerialError> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(value)
+      .map_err(|err| SerialError::Io(format!("Failed to encode {}: {err}", self.label)))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+  }
+}
+
+/// Checks a user-chosen key such as a preset name or alias and returns it
+/// trimmed: letters, digits, space, `-`, `_` and `.`, with no leading dot or
+/// `..`, so it can never be read as a path. `kind` names it in the error.
+pub fn validate_name<'a>(kind: &str, name: &'a str) -> Result<&'a str, SerialError> {
+  let name = name.trim();
+  let valid = !name.is_empty()
+    && name.len() <= MAX_NAME_LEN
+    && !name.starts_with('.')
+    && !name.contains("..")
+    && name
+      .chars()
+      .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'));
+  if valid {
+    Ok(name)
+  } else {
+    Err(SerialError::InvalidInput(format!(
+      "Invalid {kind} \"{name}\": use up to {MAX_NAME_LEN} letters, digits, spaces, '-', '_' \
+       or '.'"
+    )))
+  }
+}
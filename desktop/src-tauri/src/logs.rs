@@ -1,53 +1,297 @@
 //! Log persistence helpers for the desktop app.
-//! Writes session logs to OS-specific default locations with a safe fallback.
+//! Writes session logs under the app log dir unless the caller picks a path.
 
 use std::{
-  env,
-  fs,
-  io,
-  path::{Path, PathBuf},
+  fs::{self, File, OpenOptions},
+  io::{self, Read, Seek, SeekFrom, Write},
+  path::{Component, Path, PathBuf},
+  time::SystemTime,
 };
 
-fn preferred_log_path() -> PathBuf {
-  if cfg!(target_os = "windows") {
-    PathBuf::from(r"C:\Logs\rs485.log")
-  } else {
-    PathBuf::from("/home/pi/logs/rs485.log")
+use tauri::{AppHandle, Manager, Runtime};
+
+const DEFAULT_LOG_FILE: &str = "rs485.log";
+const DEFAULT_JSONL_LOG_FILE: &str = "rs485.jsonl";
+/// Rotation defaults: roll over past 10 MB, keeping `rs485.log.1`..`.5`.
+const DEFAULT_MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_LOG_FILES: usize = 5;
+/// Default tail size returned by `read_session_log`.
+const DEFAULT_READ_BYTES: usize = 256 * 1024;
+
+fn log_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_log_dir()
+    .map_err(|err| format!("App log dir unavailable: {err}"))
+}
+
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+  Rx,
+  Tx,
+}
+
+/// One structured session log record, written as a single JSON line.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+  pub ts: String,
+  pub direction: Direction,
+  pub format: String,
+  pub text: String,
+  pub hex: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub note: Option<String>,
+}
+
+/// Size and mtime of a file in the app log dir, for `list_session_logs`.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileInfo {
+  pub name: String,
+  pub path: String,
+  pub size: u64,
+  pub modified: Option<String>,
+}
+
+/// `path` if absolute, else relative to the app log dir; defaults to `default` there.
+fn resolve_log_path<R: Runtime>(
+  app: &AppHandle<R>,
+  path: Option<String>,
+  default: &str,
+) -> Result<PathBuf, String> {
+  match path.filter(|path| !path.trim().is_empty()) {
+    Some(path) if Path::new(&path).is_absolute() => Ok(PathBuf::from(path)),
+    Some(path) => Ok(log_dir(app)?.join(path)),
+    None => Ok(log_dir(app)?.join(default)),
   }
 }
 
-fn fallback_log_path() -> Option<PathBuf> {
-  if cfg!(target_os = "windows") {
-    env::var("USERPROFILE")
-      .ok()
-      .map(|home| Path::new(&home).join("Logs").join("rs485.log"))
-  } else {
-    env::var("HOME")
-      .ok()
-      .map(|home| Path::new(&home).join("logs").join("rs485.log"))
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+  let mut name = path.as_os_str().to_owned();
+  name.push(format!(".{index}"));
+  PathBuf::from(name)
+}
+
+/// Once `path` exceeds `max_bytes`, shifts `path.N` to `path.N+1` (dropping
+/// anything past `max_files`) and moves `path` to `path.1`.
+fn rotate_log(path: &Path, max_bytes: u64, max_files: usize) -> Result<(), io::Error> {
+  match fs::metadata(path) {
+    Ok(meta) if meta.len() > max_bytes => {}
+    _ => return Ok(()),
+  }
+  if max_files == 0 {
+    return fs::remove_file(path);
+  }
+  match fs::remove_file(backup_path(path, max_files)) {
+    Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+    _ => {}
   }
+  for index in (1..max_files).rev() {
+    let from = backup_path(path, index);
+    if from.exists() {
+      fs::rename(&from, backup_path(path, index + 1))?;
+    }
+  }
+  fs::rename(path, backup_path(path, 1))
 }
 
-fn write_log(path: &Path, contents: &str) -> Result<(), io::Error> {
+/// Where `save_session_log` writes when no path is given.
+pub(crate) fn default_log_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+  resolve_log_path(app, None, DEFAULT_LOG_FILE)
+}
+
+fn write_log(
+  path: &Path,
+  contents: &str,
+  append: bool,
+  max_bytes: u64,
+  max_files: usize,
+) -> Result<(), io::Error> {
   if let Some(parent) = path.parent() {
     fs::create_dir_all(parent)?;
   }
-  fs::write(path, contents)
+  rotate_log(path, max_bytes, max_files)?;
+  let mut file = OpenOptions::new()
+    .create(true)
+    .write(true)
+    .append(append)
+    .truncate(!append)
+    .open(path)?;
+  file.write_all(contents.as_bytes())
 }
 
+/// Writes `contents` to `path` (see `resolve_log_path`) and returns the
+/// resolved path, which is absolute since the log dir is. With `append`
+/// the file is extended instead of replaced; either way it is rotated first
+/// once it has grown past `max_bytes` (default 10 MB), keeping `max_files`
+/// backups (default 5).
 #[tauri::command]
-pub fn save_session_log(contents: String) -> Result<String, String> {
-  let preferred = preferred_log_path();
-  match write_log(&preferred, &contents) {
-    Ok(()) => Ok(preferred.display().to_string()),
-    Err(err) => {
-      let fallback = fallback_log_path().filter(|path| path != &preferred);
-      if let Some(path) = fallback {
-        if write_log(&path, &contents).is_ok() {
-          return Ok(path.display().to_string());
-        }
-      }
-      Err(err.to_string())
-    }
+pub fn save_session_log<R: Runtime>(
+  app: AppHandle<R>,
+  contents: String,
+  path: Option<String>,
+  append: Option<bool>,
+  max_bytes: Option<u64>,
+  max_files: Option<usize>,
+) -> Result<String, String> {
+  let path = resolve_log_path(&app, path, DEFAULT_LOG_FILE)?;
+  write_log(
+    &path,
+    &contents,
+    append.unwrap_or(false),
+    max_bytes.unwrap_or(DEFAULT_MAX_LOG_BYTES),
+    max_files.unwrap_or(DEFAULT_MAX_LOG_FILES),
+  )
+  .map_err(|err| format!("{}: {err}", path.display()))?;
+  Ok(path.display().to_string())
+}
+
+/// Appends `entries` as JSON lines (default `rs485.jsonl` in the app log
+/// dir), rotating like `save_session_log`. Returns the resolved path.
+#[tauri::command]
+pub fn save_session_log_jsonl<R: Runtime>(
+  app: AppHandle<R>,
+  entries: Vec<LogEntry>,
+  path: Option<String>,
+) -> Result<String, String> {
+  let path = resolve_log_path(&app, path, DEFAULT_JSONL_LOG_FILE)?;
+  let mut contents = String::new();
+  for entry in &entries {
+    let line = serde_json::to_string(entry).map_err(|err| err.to_string())?;
+    contents.push_str(&line);
+    contents.push('\n');
+  }
+  write_log(&path, &contents, true, DEFAULT_MAX_LOG_BYTES, DEFAULT_MAX_LOG_FILES)
+    .map_err(|err| format!("{}: {err}", path.display()))?;
+  Ok(path.display().to_string())
+}
+
+/// Resolves a caller-supplied path for reading: it must be relative and stay
+/// inside the app log dir, so the viewer can't be pointed at arbitrary files.
+fn resolve_readable_log<R: Runtime>(
+  app: &AppHandle<R>,
+  path: Option<String>,
+) -> Result<PathBuf, String> {
+  let dir = log_dir(app)?;
+  let Some(path) = path.filter(|path| !path.trim().is_empty()) else {
+    return Ok(dir.join(DEFAULT_LOG_FILE));
+  };
+  let relative = Path::new(&path);
+  if !relative
+    .components()
+    .all(|component| matches!(component, Component::Normal(_)))
+  {
+    return Err(format!("Log path must be a file name inside {}", dir.display()));
+  }
+  let resolved = fs::canonicalize(dir.join(relative)).map_err(|err| format!("{path}: {err}"))?;
+  let dir = fs::canonicalize(&dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+  if !resolved.starts_with(&dir) {
+    return Err(format!("Log path must be a file name inside {}", dir.display()));
+  }
+  Ok(resolved)
+}
+
+/// Reads at most `max_bytes` from the end of `path`, starting on a UTF-8
+/// character boundary.
+fn read_tail(path: &Path, max_bytes: usize) -> Result<String, io::Error> {
+  let mut file = File::open(path)?;
+  let len = file.metadata()?.len();
+  let start = len.saturating_sub(max_bytes as u64);
+  file.seek(SeekFrom::Start(start))?;
+  let mut bytes = Vec::with_capacity((len - start) as usize);
+  file.read_to_end(&mut bytes)?;
+  // A cut mid-character leaves up to three continuation bytes at the front.
+  let skip = bytes
+    .iter()
+    .take(3)
+    .take_while(|byte| (**byte & 0xC0) == 0x80)
+    .count();
+  Ok(String::from_utf8_lossy(&bytes[skip..]).into_owned())
+}
+
+/// Tail of a saved log (default `rs485.log`, last 256 KiB). `path` is
+/// relative to the app log dir.
+#[tauri::command]
+pub fn read_session_log<R: Runtime>(
+  app: AppHandle<R>,
+  path: Option<String>,
+  max_bytes: Option<usize>,
+) -> Result<String, String> {
+  let path = resolve_readable_log(&app, path)?;
+  read_tail(&path, max_bytes.unwrap_or(DEFAULT_READ_BYTES))
+    .map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Files in the app log dir, newest first.
+#[tauri::command]
+pub fn list_session_logs<R: Runtime>(app: AppHandle<R>) -> Result<Vec<LogFileInfo>, String> {
+  let dir = log_dir(&app)?;
+  let entries = match fs::read_dir(&dir) {
+    Ok(entries) => entries,
+    Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(err) => return Err(format!("{}: {err}", dir.display())),
+  };
+  let mut logs: Vec<(Option<SystemTime>, LogFileInfo)> = entries
+    .filter_map(Result::ok)
+    .filter_map(|entry| {
+      let meta = entry.metadata().ok().filter(fs::Metadata::is_file)?;
+      let modified = meta.modified().ok();
+      let info = LogFileInfo {
+        name: entry.file_name().to_string_lossy().into_owned(),
+        path: entry.path().display().to_string(),
+        size: meta.len(),
+        modified: modified.map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()),
+      };
+      Some((modified, info))
+    })
+    .collect();
+  logs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+  Ok(logs.into_iter().map(|(_, info)| info).collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rotates_past_max_bytes() {
+    let dir = std::env::temp_dir().join(format!("rs485-logs-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("rs485.log");
+    let read = |path: &Path| fs::read_to_string(path).unwrap();
+
+    // Under the limit: appends accumulate in place.
+    write_log(&path, "aaaaaa", true, 10, 2).unwrap();
+    write_log(&path, "aaaaaa", true, 10, 2).unwrap();
+    assert_eq!(read(&path), "aaaaaaaaaaaa");
+    assert!(!backup_path(&path, 1).exists());
+
+    // Each write into an oversized file rolls the chain first.
+    write_log(&path, "bbbbbbbbbbbb", true, 10, 2).unwrap();
+    assert_eq!(read(&path), "bbbbbbbbbbbb");
+    assert_eq!(read(&backup_path(&path, 1)), "aaaaaaaaaaaa");
+
+    write_log(&path, "cc", true, 10, 2).unwrap();
+    assert_eq!(read(&path), "cc");
+    assert_eq!(read(&backup_path(&path, 1)), "bbbbbbbbbbbb");
+    assert_eq!(read(&backup_path(&path, 2)), "aaaaaaaaaaaa");
+
+    // The oldest backup falls off once `max_files` is reached.
+    write_log(&path, "ccccccccc", true, 10, 2).unwrap();
+    write_log(&path, "dd", true, 10, 2).unwrap();
+    assert_eq!(read(&path), "dd");
+    assert_eq!(read(&backup_path(&path, 1)), "ccccccccccc");
+    assert_eq!(read(&backup_path(&path, 2)), "bbbbbbbbbbbb");
+    assert!(!backup_path(&path, 3).exists());
+
+    // Without append the live file is replaced, but still rotated when large.
+    write_log(&path, "eeeeeeeeeeee", false, 10, 2).unwrap();
+    write_log(&path, "ff", false, 10, 2).unwrap();
+    assert_eq!(read(&path), "ff");
+    assert_eq!(read(&backup_path(&path, 1)), "eeeeeeeeeeee");
+
+    fs::remove_dir_all(&dir).unwrap();
   }
 }
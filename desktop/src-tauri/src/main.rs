@@ -3,49 +3,205 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod alarms;
+mod api_metrics;
 mod api_server;
+mod backend;
+mod checksum;
+mod frame_parser;
+mod framing;
+mod json_store;
 mod logs;
 mod menu;
+mod modbus;
+mod modbus_poll;
+#[cfg(feature = "mqtt")]
+mod mqtt_bridge;
+mod netif;
+mod port_alias;
+mod recent_ports;
+mod register_map;
 mod serial;
+mod serial_io;
+mod serial_preset;
+mod serial_record;
 mod system;
-
-use std::sync::Mutex;
+mod telemetry_bridge;
+mod telemetry_csv;
+mod telemetry_db;
+mod telemetry_lp;
 
 use tauri::{
   tray::{TrayIconBuilder, TrayIconEvent},
-  Emitter, Manager,
+  AppHandle, Emitter, Manager, Runtime,
 };
 
 use tauri_plugin_autostart::MacosLauncher;
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
-use crate::api_server::spawn_api_server;
-use crate::menu::{build_menu, show_main_window};
+use crate::api_server::{spawn_api_server, ApiServerHandle};
+use crate::backend::{
+  backend_status, kill_backend, restart_backend, restart_sidecar, spawn_backend, start_watchdog,
+  BackendState, WatchdogConfig,
+};
+use crate::checksum::{serial_append_checksum, serial_verify_checksum};
+use crate::frame_parser::{parse_serial_frame, test_frame_parser};
+use crate::framing::{cobs_decode, cobs_encode, slip_decode, slip_encode};
+use crate::menu::{
+  build_menu, refresh_tray_status, show_main_window, tray_icon, RECENT_PORT_PREFIX, TRAY_ID,
+};
+use crate::modbus::{
+  modbus_crc16, modbus_read_coils, modbus_read_holding_registers, modbus_scan_slaves,
+  modbus_write_multiple_registers, modbus_write_single_coil, modbus_write_single_register,
+};
+use crate::modbus::tcp::{
+  modbus_tcp_read_coils, modbus_tcp_read_holding_registers, modbus_tcp_write_multiple_registers,
+  modbus_tcp_write_single_coil, modbus_tcp_write_single_register,
+};
+use crate::modbus_poll::{start_modbus_poll, stop_modbus_poll};
+use crate::netif::network_interfaces;
+use crate::port_alias::{list_port_aliases, remove_port_alias, set_port_alias};
+use crate::recent_ports::{clear_recent_ports, list_recent_ports, recent_ports};
+use crate::register_map::{modbus_load_register_map, modbus_read_mapped, RegisterMaps};
 use crate::serial::{
-  close_serial_port, list_serial_ports, open_serial_port, read_serial_data, write_serial_data,
-  SerialState,
+  clear_serial_buffer, close_serial_port, detect_serial_baud, list_serial_ports,
+  list_serial_ports_detailed, open_serial_port, open_serial_ports, pulse_serial_dtr,
+  read_serial_available, read_serial_data, read_serial_lines, read_serial_until, reset_serial_stats,
+  send_serial_break, serial_bytes_available, serial_stats, set_serial_baud, set_serial_dtr,
+  set_serial_rts, start_port_watch, start_serial_stream, stop_port_watch, stop_serial_stream,
+  write_serial_data, SerialState,
+};
+use crate::serial_preset::{
+  delete_serial_preset, export_serial_profile, import_serial_profile, list_serial_presets,
+  load_serial_preset, save_serial_preset,
 };
-use crate::system::system_info_string;
-use crate::logs::save_session_log;
+use crate::serial_record::{replay_serial_file, start_serial_record, stop_serial_record};
+use crate::system::{cpu_load, power_status, system_info, system_info_string};
+use crate::telemetry_bridge::{serial_to_telemetry, stop_serial_to_telemetry};
+use crate::logs::{list_session_logs, read_session_log, save_session_log, save_session_log_jsonl};
+
+/// Exits at once when idle; with a serial session active, asks first so a
+/// stray Cmd+Q doesn't drop the link mid-capture.
+fn request_quit<R: Runtime>(app: &AppHandle<R>) {
+  let active = app
+    .try_state::<SerialState>()
+    .is_some_and(|state| state.session_active());
+  if !active {
+    app.exit(0);
+    return;
+  }
+  let handle = app.clone();
+  app
+    .dialog()
+    .message("A serial session is active. Quit anyway?")
+    .title("Quit")
+    .kind(MessageDialogKind::Warning)
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      "Quit".to_string(),
+      "Cancel".to_string(),
+    ))
+    .show(move |confirmed| {
+      if confirmed {
+        handle.exit(0);
+      }
+    });
+}
 
 fn main() {
   tauri::Builder::default()
     .invoke_handler(tauri::generate_handler![
       list_serial_ports,
+      list_serial_ports_detailed,
+      start_port_watch,
+      stop_port_watch,
+      start_serial_record,
+      stop_serial_record,
+      replay_serial_file,
+      save_serial_preset,
+      load_serial_preset,
+      list_serial_presets,
+      delete_serial_preset,
+      export_serial_profile,
+      import_serial_profile,
       open_serial_port,
       close_serial_port,
+      open_serial_ports,
       write_serial_data,
       read_serial_data,
-      save_session_log
+      read_serial_available,
+      read_serial_until,
+      start_serial_stream,
+      stop_serial_stream,
+      serial_to_telemetry,
+      stop_serial_to_telemetry,
+      set_serial_dtr,
+      set_serial_rts,
+      pulse_serial_dtr,
+      read_serial_lines,
+      send_serial_break,
+      set_serial_baud,
+      detect_serial_baud,
+      clear_serial_buffer,
+      serial_append_checksum,
+      serial_verify_checksum,
+      cobs_encode,
+      cobs_decode,
+      slip_encode,
+      parse_serial_frame,
+      test_frame_parser,
+      slip_decode,
+      serial_bytes_available,
+      serial_stats,
+      reset_serial_stats,
+      modbus_read_holding_registers,
+      modbus_write_single_register,
+      modbus_write_multiple_registers,
+      modbus_read_coils,
+      modbus_write_single_coil,
+      modbus_scan_slaves,
+      modbus_crc16,
+      modbus_tcp_read_holding_registers,
+      modbus_tcp_read_coils,
+      modbus_tcp_write_single_register,
+      modbus_tcp_write_multiple_registers,
+      modbus_tcp_write_single_coil,
+      start_modbus_poll,
+      stop_modbus_poll,
+      modbus_load_register_map,
+      modbus_read_mapped,
+      save_session_log,
+      save_session_log_jsonl,
+      read_session_log,
+      list_session_logs,
+      system_info,
+      power_status,
+      cpu_load,
+      network_interfaces,
+      list_recent_ports,
+      clear_recent_ports,
+      set_port_alias,
+      remove_port_alias,
+      list_port_aliases,
+      backend_status,
+      restart_backend
     ])
     .plugin(tauri_plugin_shell::init())
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, None))
     .setup(|app| {
-      // 1) Spawn embedded Rust REST/WS backend
+      // 1) Spawn embedded Rust REST/WS backend, or the Python sidecar under a watchdog
       let handle = app.handle().clone();
-      if let Err(e) = spawn_api_server(&handle) {
+      if backend::sidecar_enabled() {
+        let state = BackendState::default();
+        let config = WatchdogConfig::from_env();
+        if let Err(e) = spawn_backend(&handle, &state, &config) {
+          let _ = handle.emit("backend:spawn_failed", format!("{e:?}"));
+        }
+        start_watchdog(handle.clone(), state.clone(), config.clone());
+        app.manage(state);
+        app.manage(config);
+      } else if let Err(e) = spawn_api_server(&handle) {
         let _ = handle.emit("backend:spawn_failed", format!("{e:?}"));
       }
 
@@ -55,8 +211,11 @@ fn main() {
 
       // 3) Tray menu + tray icon
       let tray_menu = build_menu(&handle)?;
-      let _tray = TrayIconBuilder::new()
-        .menu(&tray_menu)
+      let mut tray = TrayIconBuilder::with_id(TRAY_ID).menu(&tray_menu);
+      if let Some(icon) = tray_icon(&handle, false) {
+        tray = tray.icon(icon);
+      }
+      tray
         .on_tray_icon_event(|tray, event| match event {
           TrayIconEvent::Click { .. } => {
             show_main_window(&tray.app_handle());
@@ -66,9 +225,9 @@ fn main() {
         .build(app)?;
 
       // Store state globally
-      app.manage(SerialState {
-        port: Mutex::new(None),
-      });
+      app.manage(SerialState::default());
+      app.manage(RegisterMaps::default());
+      refresh_tray_status(&handle);
 
       Ok(())
     })
@@ -82,7 +241,7 @@ fn main() {
             "{} v{}\nRS-485 Enterprise Telemetry Desktop\n\n{}",
             info.name,
             info.version,
-            system_info_string()
+            system_info_string(app)
           );
           app
             .dialog()
@@ -92,10 +251,30 @@ fn main() {
             .buttons(MessageDialogButtons::Ok)
             .show(|_| {});
         }
-        "quit" => {
-          app.exit(0);
+        "menu.restart_backend" => {
+          if let Err(err) = restart_sidecar(app) {
+            eprintln!("[backend] restart failed: {err}");
+            let _ = app.emit("backend:spawn_failed", err);
+          }
+        }
+        "menu.connect" => {
+          show_main_window(app);
+          let _ = app.emit("menu:connect", ());
+        }
+        "menu.disconnect" => {
+          let _ = app.emit("menu:disconnect", ());
+        }
+        "menu.save_log" => {
+          let _ = app.emit("menu:save_log", ());
+        }
+        "quit" => request_quit(app),
+        _ => {
+          let index = id.strip_prefix(RECENT_PORT_PREFIX).and_then(|n| n.parse::<usize>().ok());
+          if let Some(entry) = index.and_then(|index| recent_ports(app).into_iter().nth(index)) {
+            show_main_window(app);
+            let _ = app.emit("menu:open_recent_port", entry);
+          }
         }
-        _ => {}
       }
     })
     .on_window_event(|window, event| {
@@ -114,6 +293,22 @@ fn main() {
         }
       }
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      // `code` is set when we called `exit` ourselves; anything else (e.g.
+      // Quit from the macOS dock) goes through the same confirmation.
+      if let tauri::RunEvent::ExitRequested { api, code: None, .. } = &event {
+        api.prevent_exit();
+        request_quit(app);
+      }
+      if let tauri::RunEvent::Exit = event {
+        if let Some(server) = app.try_state::<ApiServerHandle>() {
+          server.shutdown();
+        }
+        if let Some(backend) = app.try_state::<BackendState>() {
+          kill_backend(app, &backend, app.state::<WatchdogConfig>().terminate_grace());
+        }
+      }
+    });
 }
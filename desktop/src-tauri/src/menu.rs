@@ -1,25 +1,161 @@
 //! Menu and tray helpers for the desktop app.
-//! Provides menu construction and window focus helpers.
+//! Provides menu construction, tray status and window focus helpers.
 
 use tauri::{
-  menu::{MenuBuilder, MenuItem, PredefinedMenuItem},
+  image::Image,
+  menu::{MenuBuilder, MenuItem, SubmenuBuilder},
   AppHandle, Manager, Runtime,
 };
 
+use crate::backend::sidecar_enabled;
+use crate::recent_ports::recent_ports;
+use crate::serial::{SerialConfig, SerialState};
+
+pub const TRAY_ID: &str = "main";
+/// Recent Ports items are `menu.recent_port.<index>` into the MRU list.
+pub const RECENT_PORT_PREFIX: &str = "menu.recent_port.";
+
+/// Shortcuts for the custom items; `CmdOrCtrl` maps to Cmd on macOS. H/M/W
+/// are avoided since macOS reserves them for hide, minimize and close.
+const QUIT_ACCELERATOR: &str = "CmdOrCtrl+Q";
+const CONNECT_ACCELERATOR: &str = "CmdOrCtrl+K";
+const DISCONNECT_ACCELERATOR: &str = "CmdOrCtrl+D";
+const SAVE_LOG_ACCELERATOR: &str = "CmdOrCtrl+L";
+
 pub fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Menu<R>> {
-  let quit = PredefinedMenuItem::quit(app, None)?;
+  // A custom item rather than the predefined Quit, which exits natively and
+  // would skip the confirmation in main.rs.
+  let quit = MenuItem::with_id(app, "quit", "Quit", true, Some(QUIT_ACCELERATOR))?;
 
   // Custom menu items with stable IDs (so matching works reliably)
   let about = MenuItem::with_id(app, "menu.about", "About", true, None::<&str>)?;
-  let menu = MenuBuilder::new(app)
+  let restart = MenuItem::with_id(
+    app,
+    "menu.restart_backend",
+    "Restart Backend",
+    true,
+    None::<&str>,
+  )?;
+
+  // Connect and Disconnect follow the port state; `refresh_menus` keeps them current.
+  let open_ports: Vec<String> = open_configs(app).into_iter().map(|config| config.port).collect();
+  let connect = MenuItem::with_id(
+    app,
+    "menu.connect",
+    "Connect",
+    open_ports.is_empty(),
+    Some(CONNECT_ACCELERATOR),
+  )?;
+  let disconnect_text = if open_ports.is_empty() {
+    "Disconnect".to_string()
+  } else {
+    format!("Disconnect {}", open_ports.join(", "))
+  };
+  let disconnect = MenuItem::with_id(
+    app,
+    "menu.disconnect",
+    disconnect_text,
+    !open_ports.is_empty(),
+    Some(DISCONNECT_ACCELERATOR),
+  )?;
+  let save_log = MenuItem::with_id(
+    app,
+    "menu.save_log",
+    "Save Session Log",
+    true,
+    Some(SAVE_LOG_ACCELERATOR),
+  )?;
+
+  let mut recent = SubmenuBuilder::with_id(app, "menu.recent_ports", "Recent Ports");
+  let entries = recent_ports(app);
+  if entries.is_empty() {
+    let none =
+      MenuItem::with_id(app, "menu.recent_ports.none", "No Recent Ports", false, None::<&str>)?;
+    recent = recent.item(&none);
+  }
+  for (index, entry) in entries.iter().enumerate() {
+    let id = format!("{RECENT_PORT_PREFIX}{index}");
+    let item = MenuItem::with_id(app, id, entry.menu_text(), true, None::<&str>)?;
+    recent = recent.item(&item);
+  }
+  let recent = recent.build()?;
+
+  let mut builder = MenuBuilder::new(app)
     .item(&about)
     .separator()
-    .item(&quit)
-    .build()?;
+    .item(&connect)
+    .item(&disconnect)
+    .item(&recent)
+    .item(&save_log);
+  // Only the Python sidecar can be bounced independently of the app.
+  if sidecar_enabled() {
+    builder = builder.item(&restart);
+  }
+  let menu = builder.separator().item(&quit).build()?;
 
   Ok(menu)
 }
 
+/// Rebuilds the app and tray menus so dynamic items (port state, Recent Ports)
+/// stay current.
+pub fn refresh_menus<R: Runtime>(app: &AppHandle<R>) {
+  if let Err(err) = build_menu(app).and_then(|menu| app.set_menu(menu)) {
+    eprintln!("[system] app menu refresh failed: {err}");
+  }
+  if let Some(tray) = app.tray_by_id(TRAY_ID) {
+    if let Err(err) = build_menu(app).and_then(|menu| tray.set_menu(Some(menu))) {
+      eprintln!("[system] tray menu refresh failed: {err}");
+    }
+  }
+}
+
+/// Configs of the open ports; empty before `SerialState` is managed.
+fn open_configs<R: Runtime>(app: &AppHandle<R>) -> Vec<SerialConfig> {
+  app.try_state::<SerialState>().map(|state| state.open_configs()).unwrap_or_default()
+}
+
+/// Tooltip for the tray, e.g. `RS-485: connected /dev/ttyUSB0 @ 9600`.
+fn tray_tooltip(open: &[SerialConfig]) -> String {
+  if open.is_empty() {
+    return "RS-485: disconnected".to_string();
+  }
+  let ports: Vec<String> =
+    open.iter().map(|config| format!("{} @ {}", config.port, config.baud)).collect();
+  format!("RS-485: connected {}", ports.join(", "))
+}
+
+/// The app icon while connected; a dimmed greyscale copy of it otherwise.
+pub fn tray_icon<R: Runtime>(app: &AppHandle<R>, connected: bool) -> Option<Image<'static>> {
+  let icon = app.default_window_icon()?;
+  if connected {
+    return Some(icon.to_owned());
+  }
+  let mut rgba = icon.rgba().to_vec();
+  for pixel in rgba.chunks_exact_mut(4) {
+    let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(u32::from);
+    let luma = (r * 299 + g * 587 + b * 114) / 1000;
+    pixel[..3].fill(luma as u8);
+    pixel[3] /= 2;
+  }
+  Some(Image::new_owned(rgba, icon.width(), icon.height()))
+}
+
+/// Updates the tray tooltip and icon to match the serial port state.
+pub fn refresh_tray_status<R: Runtime>(app: &AppHandle<R>) {
+  let Some(tray) = app.tray_by_id(TRAY_ID) else {
+    return;
+  };
+  let open = open_configs(app);
+  if let Err(err) = tray.set_tooltip(Some(tray_tooltip(&open))) {
+    eprintln!("[system] tray tooltip update failed: {err}");
+  }
+  if let Some(icon) = tray_icon(app, !open.is_empty()) {
+    if let Err(err) = tray.set_icon(Some(icon)) {
+      eprintln!("[system] tray icon update failed: {err}");
+    }
+  }
+}
+
 pub fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
   if let Some(w) = app.get_webview_window("main") {
     let _ = w.show();
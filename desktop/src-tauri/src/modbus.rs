@@ -0,0 +1,855 @@
+//! Modbus RTU and ASCII master commands for the desktop app.
+//! Provides frame building, CRC16/LRC validation, and register reads over the open serial port.
+
+use std::{
+  io::ErrorKind,
+  sync::PoisonError,
+  thread,
+  time::{Duration, Instant},
+};
+
+use serialport::ClearBuffer;
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use crate::serial::{
+  bytes_to_hex, hex_to_bytes, stream_active, write_frame, DriverEnable, PortEntry, SerialError,
+  SerialState,
+};
+use crate::serial_io::SerialIo;
+
+pub mod tcp;
+
+pub(crate) const FC_READ_COILS: u8 = 0x01;
+const FC_READ_DISCRETE_INPUTS: u8 = 0x02;
+pub(crate) const FC_READ_HOLDING_REGISTERS: u8 = 0x03;
+const FC_READ_INPUT_REGISTERS: u8 = 0x04;
+const FC_WRITE_SINGLE_COIL: u8 = 0x05;
+const FC_WRITE_SINGLE_REGISTER: u8 = 0x06;
+const FC_WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+const EXCEPTION_FLAG: u8 = 0x80;
+/// Largest register count a single 0x03 request may ask for.
+const MAX_READ_REGISTERS: u16 = 125;
+/// Largest register count a single 0x10 request may carry.
+const MAX_WRITE_REGISTERS: usize = 123;
+/// Largest coil count a single 0x01 request may ask for.
+const MAX_READ_COILS: u16 = 2000;
+/// Longest ASCII frame: `:` + 2 × (address + 253-byte PDU + LRC) + CRLF.
+const MAX_ASCII_FRAME: usize = 513;
+
+/// Serial framing. RTU is binary with a CRC16; ASCII sends each byte as two
+/// hex characters between `:` and CRLF with an LRC, for older devices.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModbusMode {
+  #[default]
+  Rtu,
+  Ascii,
+}
+
+/// Exception code carried in a `function | 0x80` response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModbusException {
+  IllegalFunction,
+  IllegalDataAddress,
+  IllegalDataValue,
+  SlaveDeviceFailure,
+  Acknowledge,
+  SlaveDeviceBusy,
+  NegativeAcknowledge,
+  MemoryParityError,
+  GatewayPathUnavailable,
+  GatewayTargetFailedToRespond,
+  Unknown(u8),
+}
+
+impl ModbusException {
+  pub fn from_code(code: u8) -> Self {
+    match code {
+      0x01 => ModbusException::IllegalFunction,
+      0x02 => ModbusException::IllegalDataAddress,
+      0x03 => ModbusException::IllegalDataValue,
+      0x04 => ModbusException::SlaveDeviceFailure,
+      0x05 => ModbusException::Acknowledge,
+      0x06 => ModbusException::SlaveDeviceBusy,
+      0x07 => ModbusException::NegativeAcknowledge,
+      0x08 => ModbusException::MemoryParityError,
+      0x0A => ModbusException::GatewayPathUnavailable,
+      0x0B => ModbusException::GatewayTargetFailedToRespond,
+      other => ModbusException::Unknown(other),
+    }
+  }
+
+  pub fn code(self) -> u8 {
+    match self {
+      ModbusException::IllegalFunction => 0x01,
+      ModbusException::IllegalDataAddress => 0x02,
+      ModbusException::IllegalDataValue => 0x03,
+      ModbusException::SlaveDeviceFailure => 0x04,
+      ModbusException::Acknowledge => 0x05,
+      ModbusException::SlaveDeviceBusy => 0x06,
+      ModbusException::NegativeAcknowledge => 0x07,
+      ModbusException::MemoryParityError => 0x08,
+      ModbusException::GatewayPathUnavailable => 0x0A,
+      ModbusException::GatewayTargetFailedToRespond => 0x0B,
+      ModbusException::Unknown(code) => code,
+    }
+  }
+}
+
+impl std::fmt::Display for ModbusException {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let text = match self {
+      ModbusException::IllegalFunction => "Illegal function (not supported by the slave)",
+      ModbusException::IllegalDataAddress => "Illegal data address (register out of range)",
+      ModbusException::IllegalDataValue => "Illegal data value",
+      ModbusException::SlaveDeviceFailure => "Slave device failure",
+      ModbusException::Acknowledge => "Acknowledge (request accepted, still processing)",
+      ModbusException::SlaveDeviceBusy => "Slave device busy, retry later",
+      ModbusException::NegativeAcknowledge => "Negative acknowledge",
+      ModbusException::MemoryParityError => "Memory parity error",
+      ModbusException::GatewayPathUnavailable => "Gateway path unavailable",
+      ModbusException::GatewayTargetFailedToRespond => "Gateway target device failed to respond",
+      ModbusException::Unknown(_) => "Unknown exception",
+    };
+    f.write_str(text)
+  }
+}
+
+/// Error returned by Modbus commands. Serializes as `{ kind, message }`, plus
+/// `code` and `description` for slave exception responses.
+#[derive(Clone, Debug)]
+pub enum ModbusError {
+  Serial(SerialError),
+  Exception { function: u8, exception: ModbusException },
+  InvalidResponse(String),
+}
+
+impl std::fmt::Display for ModbusError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ModbusError::Serial(err) => write!(f, "{err}"),
+      ModbusError::Exception { function, exception } => write!(
+        f,
+        "Modbus exception 0x{:02X} for function 0x{function:02X}: {exception}",
+        exception.code()
+      ),
+      ModbusError::InvalidResponse(msg) => write!(f, "Invalid Modbus response: {msg}"),
+    }
+  }
+}
+
+impl std::error::Error for ModbusError {}
+
+impl serde::Serialize for ModbusError {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+    let kind = match self {
+      ModbusError::Serial(err) => err.kind(),
+      ModbusError::Exception { .. } => "ModbusException",
+      ModbusError::InvalidResponse(_) => "InvalidResponse",
+    };
+    let mut out = serializer.serialize_struct("ModbusError", 4)?;
+    out.serialize_field("kind", kind)?;
+    out.serialize_field("message", &self.to_string())?;
+    if let ModbusError::Exception { exception, .. } = self {
+      out.serialize_field("code", &exception.code())?;
+      out.serialize_field("description", &exception.to_string())?;
+    }
+    out.end()
+  }
+}
+
+impl From<SerialError> for ModbusError {
+  fn from(err: SerialError) -> Self {
+    ModbusError::Serial(err)
+  }
+}
+
+impl From<std::io::Error> for ModbusError {
+  fn from(err: std::io::Error) -> Self {
+    ModbusError::Serial(err.into())
+  }
+}
+
+impl From<serialport::Error> for ModbusError {
+  fn from(err: serialport::Error) -> Self {
+    ModbusError::Serial(err.into())
+  }
+}
+
+impl<T> From<PoisonError<T>> for ModbusError {
+  fn from(err: PoisonError<T>) -> Self {
+    ModbusError::Serial(err.into())
+  }
+}
+
+/// Modbus CRC16 (reflected polynomial 0xA001, initial value 0xFFFF).
+/// Transmitted low byte first.
+pub(crate) fn crc16(data: &[u8]) -> u16 {
+  let mut crc: u16 = 0xFFFF;
+  for &byte in data {
+    crc ^= byte as u16;
+    for _ in 0..8 {
+      if crc & 0x0001 != 0 {
+        crc = (crc >> 1) ^ 0xA001;
+      } else {
+        crc >>= 1;
+      }
+    }
+  }
+  crc
+}
+
+/// Modbus ASCII LRC: two's complement of the byte sum.
+pub(crate) fn lrc(data: &[u8]) -> u8 {
+  data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b)).wrapping_neg()
+}
+
+/// The 3.5 character silence that delimits RTU frames. Above 19200 baud the
+/// spec fixes it at 1.75 ms instead of scaling with the bit rate.
+fn frame_silence(baud: u32) -> Duration {
+  if baud == 0 || baud > 19_200 {
+    return Duration::from_micros(1750);
+  }
+  // 3.5 characters of 11 bits (start + 8 data + parity/stop + stop), in µs.
+  Duration::from_micros(38_500_000 / u64::from(baud))
+}
+
+fn invalid(msg: impl Into<String>) -> ModbusError {
+  ModbusError::InvalidResponse(msg.into())
+}
+
+/// Reads exactly `buf.len()` bytes, failing with a timeout once `deadline` passes.
+fn read_exact_until(
+  port: &mut dyn SerialIo,
+  buf: &mut [u8],
+  deadline: Instant,
+) -> Result<(), ModbusError> {
+  let mut filled = 0;
+  while filled < buf.len() {
+    let now = Instant::now();
+    if now >= deadline {
+      return Err(SerialError::Timeout.into());
+    }
+    port.set_timeout(deadline - now)?;
+    match port.read(&mut buf[filled..]) {
+      Ok(0) => continue,
+      Ok(n) => filled += n,
+      Err(err) if err.kind() == ErrorKind::TimedOut => return Err(SerialError::Timeout.into()),
+      Err(err) => return Err(err.into()),
+    }
+  }
+  Ok(())
+}
+
+/// Length of a normal (non-exception) response body after the function code.
+enum ResponseLen {
+  /// A byte-count byte followed by that many data bytes.
+  ByteCount,
+  Fixed(usize),
+}
+
+fn response_len(function: u8) -> Result<ResponseLen, ModbusError> {
+  match function {
+    FC_READ_COILS | FC_READ_DISCRETE_INPUTS | FC_READ_HOLDING_REGISTERS
+    | FC_READ_INPUT_REGISTERS => Ok(ResponseLen::ByteCount),
+    FC_WRITE_SINGLE_COIL | FC_WRITE_SINGLE_REGISTER | FC_WRITE_MULTIPLE_REGISTERS => {
+      Ok(ResponseLen::Fixed(4))
+    }
+    other => Err(invalid(format!("unsupported function 0x{other:02X}"))),
+  }
+}
+
+/// Sends one RTU request and returns the response PDU (function code + data)
+/// after validating the slave address, function code, and CRC.
+fn rtu_transaction(
+  port: &mut dyn SerialIo,
+  slave: u8,
+  pdu: &[u8],
+  timeout: Duration,
+  driver_enable: Option<DriverEnable>,
+) -> Result<Vec<u8>, ModbusError> {
+  let function = pdu[0];
+  let mut frame = Vec::with_capacity(pdu.len() + 3);
+  frame.push(slave);
+  frame.extend_from_slice(pdu);
+  let crc = crc16(&frame);
+  frame.extend_from_slice(&crc.to_le_bytes());
+
+  thread::sleep(frame_silence(port.baud_rate().unwrap_or(9600)));
+  port.clear(ClearBuffer::Input)?;
+  write_frame(port, &frame, driver_enable, None)?;
+
+  let deadline = Instant::now() + timeout;
+  let mut response = vec![0u8; 2];
+  read_exact_until(port, &mut response, deadline)?;
+  if response[0] != slave {
+    return Err(invalid(format!("expected slave {slave}, got {}", response[0])));
+  }
+
+  let body_len = if response[1] == function | EXCEPTION_FLAG {
+    1
+  } else if response[1] == function {
+    match response_len(function)? {
+      ResponseLen::Fixed(len) => len,
+      ResponseLen::ByteCount => {
+        let mut count = [0u8; 1];
+        read_exact_until(port, &mut count, deadline)?;
+        response.push(count[0]);
+        count[0] as usize
+      }
+    }
+  } else {
+    return Err(invalid(format!(
+      "expected function 0x{function:02X}, got 0x{:02X}",
+      response[1]
+    )));
+  };
+
+  let start = response.len();
+  response.resize(start + body_len + 2, 0);
+  read_exact_until(port, &mut response[start..], deadline)?;
+
+  let (body, crc_bytes) = response.split_at(response.len() - 2);
+  if crc16(body).to_le_bytes() != crc_bytes {
+    return Err(invalid("CRC mismatch"));
+  }
+  response_pdu(function, &body[1..])
+}
+
+/// Turns an exception response into its typed error; otherwise returns `pdu`.
+fn response_pdu(function: u8, pdu: &[u8]) -> Result<Vec<u8>, ModbusError> {
+  if pdu[0] == function | EXCEPTION_FLAG {
+    return Err(ModbusError::Exception {
+      function,
+      exception: ModbusException::from_code(pdu[1]),
+    });
+  }
+  Ok(pdu.to_vec())
+}
+
+/// `:` + uppercase hex of `slave`, `pdu` and the LRC + CRLF.
+fn ascii_frame(slave: u8, pdu: &[u8]) -> Vec<u8> {
+  let mut adu = Vec::with_capacity(pdu.len() + 2);
+  adu.push(slave);
+  adu.extend_from_slice(pdu);
+  adu.push(lrc(&adu));
+  let mut frame = String::with_capacity(adu.len() * 2 + 3);
+  frame.push(':');
+  for byte in &adu {
+    frame.push_str(&format!("{byte:02X}"));
+  }
+  frame.push_str("\r\n");
+  frame.into_bytes()
+}
+
+/// Decodes an ASCII frame (with or without its CRLF) into address + PDU,
+/// checking the LRC.
+fn parse_ascii_frame(frame: &[u8]) -> Result<Vec<u8>, ModbusError> {
+  let text = std::str::from_utf8(frame).map_err(|_| invalid("non-ASCII bytes in frame"))?;
+  let hex = text
+    .trim_end_matches(['\r', '\n'])
+    .strip_prefix(':')
+    .ok_or_else(|| invalid("frame does not start with ':'"))?;
+  if hex.chars().any(|c| c.is_whitespace()) {
+    return Err(invalid("whitespace in frame"));
+  }
+  let mut adu = hex_to_bytes(hex).map_err(|_| invalid("frame is not valid hex"))?;
+  // Address, function code and LRC at minimum.
+  if adu.len() < 3 {
+    return Err(invalid("frame too short"));
+  }
+  let expected = adu.pop().unwrap_or_default();
+  if lrc(&adu) != expected {
+    return Err(invalid("LRC mismatch"));
+  }
+  Ok(adu)
+}
+
+/// Sends one ASCII request and returns the response PDU, validated like
+/// `rtu_transaction`. The reply is read up to its LF, so no length table is
+/// needed to find its end, but the length is still checked.
+fn ascii_transaction(
+  port: &mut dyn SerialIo,
+  slave: u8,
+  pdu: &[u8],
+  timeout: Duration,
+  driver_enable: Option<DriverEnable>,
+) -> Result<Vec<u8>, ModbusError> {
+  let function = pdu[0];
+  port.clear(ClearBuffer::Input)?;
+  write_frame(port, &ascii_frame(slave, pdu), driver_enable, None)?;
+
+  let deadline = Instant::now() + timeout;
+  let mut frame = Vec::new();
+  let mut byte = [0u8; 1];
+  // Skip any noise before the start character.
+  while byte[0] != b':' {
+    read_exact_until(port, &mut byte, deadline)?;
+  }
+  frame.push(b':');
+  while byte[0] != b'\n' {
+    if frame.len() >= MAX_ASCII_FRAME {
+      return Err(invalid("frame exceeds 513 characters without CRLF"));
+    }
+    read_exact_until(port, &mut byte, deadline)?;
+    frame.push(byte[0]);
+  }
+
+  let adu = parse_ascii_frame(&frame)?;
+  if adu[0] != slave {
+    return Err(invalid(format!("expected slave {slave}, got {}", adu[0])));
+  }
+  check_response_pdu(function, &adu[1..])
+}
+
+/// Validates a response PDU that arrived in one piece (ASCII, TCP): function
+/// code, length for that function, then exception decoding.
+fn check_response_pdu(function: u8, pdu: &[u8]) -> Result<Vec<u8>, ModbusError> {
+  let Some(&received) = pdu.first() else {
+    return Err(invalid("empty PDU"));
+  };
+  let expected_len = if received == function | EXCEPTION_FLAG {
+    2
+  } else if received == function {
+    match response_len(function)? {
+      ResponseLen::Fixed(len) => 1 + len,
+      ResponseLen::ByteCount => 2 + usize::from(*pdu.get(1).unwrap_or(&0)),
+    }
+  } else {
+    return Err(invalid(format!(
+      "expected function 0x{function:02X}, got 0x{received:02X}"
+    )));
+  };
+  if pdu.len() != expected_len {
+    return Err(invalid(format!(
+      "expected {expected_len} PDU bytes, got {}",
+      pdu.len()
+    )));
+  }
+  response_pdu(function, pdu)
+}
+
+/// Unpacks LSB-first coil bits, dropping the padding bits of the final byte.
+fn unpack_bits(bytes: &[u8], count: usize) -> Vec<bool> {
+  (0..count)
+    .map(|i| bytes[i / 8] & (1 << (i % 8)) != 0)
+    .collect()
+}
+
+/// Checks that a write response echoes the request PDU: all of it for single
+/// writes, up to the quantity for 0x10.
+fn check_write_echo(request: &[u8], response: &[u8]) -> Result<(), ModbusError> {
+  let len = if request[0] == FC_WRITE_MULTIPLE_REGISTERS { 5 } else { request.len() };
+  if response.len() != len || request[..len] != response[..] {
+    return Err(invalid("response does not echo the request"));
+  }
+  Ok(())
+}
+
+/// 0x03 request for `count` registers from `start`.
+fn read_holding_registers_pdu(start: u16, count: u16) -> Result<Vec<u8>, ModbusError> {
+  if count == 0 || count > MAX_READ_REGISTERS {
+    return Err(
+      SerialError::InvalidInput(format!(
+        "Register count must be between 1 and {MAX_READ_REGISTERS}"
+      ))
+      .into(),
+    );
+  }
+  let mut pdu = vec![FC_READ_HOLDING_REGISTERS];
+  pdu.extend_from_slice(&start.to_be_bytes());
+  pdu.extend_from_slice(&count.to_be_bytes());
+  Ok(pdu)
+}
+
+/// Register values from a 0x03 response PDU.
+fn decode_registers(response: &[u8], count: u16) -> Result<Vec<u16>, ModbusError> {
+  let byte_count = response[1] as usize;
+  if byte_count != count as usize * 2 || response.len() != 2 + byte_count {
+    return Err(invalid(format!(
+      "expected {} data bytes, got {byte_count}",
+      count * 2
+    )));
+  }
+  Ok(
+    response[2..]
+      .chunks_exact(2)
+      .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+      .collect(),
+  )
+}
+
+/// 0x01 request for `count` coils from `start`.
+fn read_coils_pdu(start: u16, count: u16) -> Result<Vec<u8>, ModbusError> {
+  if count == 0 || count > MAX_READ_COILS {
+    return Err(
+      SerialError::InvalidInput(format!("Coil count must be between 1 and {MAX_READ_COILS}"))
+        .into(),
+    );
+  }
+  let mut pdu = vec![FC_READ_COILS];
+  pdu.extend_from_slice(&start.to_be_bytes());
+  pdu.extend_from_slice(&count.to_be_bytes());
+  Ok(pdu)
+}
+
+/// Coil states from a 0x01 response PDU.
+fn decode_coils(response: &[u8], count: u16) -> Result<Vec<bool>, ModbusError> {
+  let expected = (count as usize).div_ceil(8);
+  let byte_count = response[1] as usize;
+  if byte_count != expected || response.len() != 2 + byte_count {
+    return Err(invalid(format!("expected {expected} data bytes, got {byte_count}")));
+  }
+  Ok(unpack_bits(&response[2..], count as usize))
+}
+
+fn write_single_register_pdu(addr: u16, value: u16) -> Vec<u8> {
+  let mut pdu = vec![FC_WRITE_SINGLE_REGISTER];
+  pdu.extend_from_slice(&addr.to_be_bytes());
+  pdu.extend_from_slice(&value.to_be_bytes());
+  pdu
+}
+
+fn write_multiple_registers_pdu(
+  start: u16,
+  values: &[u16],
+) -> Result<Vec<u8>, ModbusError> {
+  if values.is_empty() || values.len() > MAX_WRITE_REGISTERS {
+    return Err(
+      SerialError::InvalidInput(format!(
+        "Register count must be between 1 and {MAX_WRITE_REGISTERS}"
+      ))
+      .into(),
+    );
+  }
+  let mut pdu = vec![FC_WRITE_MULTIPLE_REGISTERS];
+  pdu.extend_from_slice(&start.to_be_bytes());
+  pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+  pdu.push((values.len() * 2) as u8);
+  for value in values {
+    pdu.extend_from_slice(&value.to_be_bytes());
+  }
+  Ok(pdu)
+}
+
+fn write_single_coil_pdu(addr: u16, on: bool) -> Vec<u8> {
+  let value: u16 = if on { 0xFF00 } else { 0x0000 };
+  let mut pdu = vec![FC_WRITE_SINGLE_COIL];
+  pdu.extend_from_slice(&addr.to_be_bytes());
+  pdu.extend_from_slice(&value.to_be_bytes());
+  pdu
+}
+
+/// Locks the open port and runs one transaction, restoring the port timeout afterwards.
+fn transact(
+  state: &PortEntry,
+  slave: u8,
+  pdu: &[u8],
+  timeout_ms: u64,
+  mode: ModbusMode,
+) -> Result<Vec<u8>, ModbusError> {
+  if !(1..=247).contains(&slave) {
+    return Err(SerialError::InvalidInput(format!("Invalid slave address: {slave}")).into());
+  }
+  if stream_active(state)? {
+    return Err(
+      SerialError::Busy("Serial stream active; stop it before Modbus requests".to_string()).into(),
+    );
+  }
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  let driver_enable = *state.driver_enable.lock()?;
+  let original_timeout = port.timeout();
+  let timeout = Duration::from_millis(timeout_ms.max(1));
+  let result = match mode {
+    ModbusMode::Rtu => rtu_transaction(port.as_mut(), slave, pdu, timeout, driver_enable),
+    ModbusMode::Ascii => ascii_transaction(port.as_mut(), slave, pdu, timeout, driver_enable),
+  };
+  // A failed transaction's error matters more than a failed restore.
+  let restored = port.set_timeout(original_timeout);
+  let response = result?;
+  restored?;
+  Ok(response)
+}
+
+#[tauri::command]
+pub fn modbus_read_holding_registers(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  slave: u8,
+  start: u16,
+  count: u16,
+  timeout_ms: u64,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<Vec<u16>, ModbusError> {
+  let state = state.entry(port_id.as_deref())?;
+  let mode = modbus_mode.unwrap_or_default();
+  read_holding_registers(&state, slave, start, count, timeout_ms, mode)
+}
+
+pub(crate) fn read_holding_registers(
+  state: &PortEntry,
+  slave: u8,
+  start: u16,
+  count: u16,
+  timeout_ms: u64,
+  mode: ModbusMode,
+) -> Result<Vec<u16>, ModbusError> {
+  let pdu = read_holding_registers_pdu(start, count)?;
+  let response = transact(state, slave, &pdu, timeout_ms, mode)?;
+  let registers = decode_registers(&response, count)?;
+  eprintln!("[modbus] read holding ok slave={slave} start={start} count={count}");
+  Ok(registers)
+}
+
+#[tauri::command]
+pub fn modbus_write_single_register(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  slave: u8,
+  addr: u16,
+  value: u16,
+  timeout_ms: u64,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<(), ModbusError> {
+  let pdu = write_single_register_pdu(addr, value);
+  let state = state.entry(port_id.as_deref())?;
+  let response = transact(&state, slave, &pdu, timeout_ms, modbus_mode.unwrap_or_default())?;
+  check_write_echo(&pdu, &response)?;
+  eprintln!("[modbus] write single ok slave={slave} addr={addr} value={value}");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn modbus_write_multiple_registers(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  slave: u8,
+  start: u16,
+  values: Vec<u16>,
+  timeout_ms: u64,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<(), ModbusError> {
+  let pdu = write_multiple_registers_pdu(start, &values)?;
+  let count = values.len();
+  let state = state.entry(port_id.as_deref())?;
+  let response = transact(&state, slave, &pdu, timeout_ms, modbus_mode.unwrap_or_default())?;
+  check_write_echo(&pdu, &response)?;
+  eprintln!("[modbus] write multiple ok slave={slave} start={start} count={count}");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn modbus_read_coils(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  slave: u8,
+  start: u16,
+  count: u16,
+  timeout_ms: u64,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<Vec<bool>, ModbusError> {
+  let state = state.entry(port_id.as_deref())?;
+  read_coils(&state, slave, start, count, timeout_ms, modbus_mode.unwrap_or_default())
+}
+
+pub(crate) fn read_coils(
+  state: &PortEntry,
+  slave: u8,
+  start: u16,
+  count: u16,
+  timeout_ms: u64,
+  mode: ModbusMode,
+) -> Result<Vec<bool>, ModbusError> {
+  let pdu = read_coils_pdu(start, count)?;
+  let response = transact(state, slave, &pdu, timeout_ms, mode)?;
+  let coils = decode_coils(&response, count)?;
+  eprintln!("[modbus] read coils ok slave={slave} start={start} count={count}");
+  Ok(coils)
+}
+
+#[tauri::command]
+pub fn modbus_write_single_coil(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  slave: u8,
+  addr: u16,
+  on: bool,
+  timeout_ms: u64,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<(), ModbusError> {
+  let pdu = write_single_coil_pdu(addr, on);
+  let state = state.entry(port_id.as_deref())?;
+  let response = transact(&state, slave, &pdu, timeout_ms, modbus_mode.unwrap_or_default())?;
+  check_write_echo(&pdu, &response)?;
+  eprintln!("[modbus] write coil ok slave={slave} addr={addr} on={on}");
+  Ok(())
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+  pub address: u8,
+  pub found: bool,
+  pub scanned: usize,
+  pub total: usize,
+}
+
+/// Probes each address in `start..=end` with a one-item read using `probe_fc`
+/// (0x01–0x04). Any well-formed reply, including an exception, counts as a
+/// slave being present. Emits `modbus:scan_progress` per address.
+#[tauri::command]
+// Each argument is a key of the frontend's `invoke` call.
+#[allow(clippy::too_many_arguments)]
+pub fn modbus_scan_slaves<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+  start: u8,
+  end: u8,
+  probe_fc: u8,
+  timeout_ms: u64,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<Vec<u8>, ModbusError> {
+  if !(FC_READ_COILS..=FC_READ_INPUT_REGISTERS).contains(&probe_fc) {
+    return Err(
+      SerialError::InvalidInput(format!("Unsupported probe function 0x{probe_fc:02X}")).into(),
+    );
+  }
+  let start = start.max(1);
+  let end = end.min(247);
+  if start > end {
+    return Err(SerialError::InvalidInput(format!("Invalid address range {start}..={end}")).into());
+  }
+  // Short probes keep a full 1–247 sweep to well under a minute.
+  let timeout_ms = timeout_ms.clamp(20, 1000);
+  let pdu = [probe_fc, 0x00, 0x00, 0x00, 0x01];
+  let total = usize::from(end - start) + 1;
+  let mut found = Vec::new();
+  let mode = modbus_mode.unwrap_or_default();
+  let state = state.entry(port_id.as_deref())?;
+
+  for (i, address) in (start..=end).enumerate() {
+    let present = match transact(&state, address, &pdu, timeout_ms, mode) {
+      Ok(_) | Err(ModbusError::Exception { .. }) => true,
+      Err(ModbusError::Serial(SerialError::Timeout)) | Err(ModbusError::InvalidResponse(_)) => {
+        false
+      }
+      Err(err) => return Err(err),
+    };
+    if present {
+      found.push(address);
+    }
+    let _ = app.emit(
+      "modbus:scan_progress",
+      ScanProgress {
+        address,
+        found: present,
+        scanned: i + 1,
+        total,
+      },
+    );
+  }
+  eprintln!("[modbus] scan done range={start}..={end} found={found:?}");
+  Ok(found)
+}
+
+/// Computes the Modbus CRC16 of `data` (hex or text) and returns it in wire
+/// order, e.g. `"C5 CD"`, so hand-built frames can be completed in the UI.
+#[tauri::command]
+pub fn modbus_crc16(data: String, format: Option<String>) -> Result<String, SerialError> {
+  let bytes = match format.as_deref() {
+    Some("hex") => hex_to_bytes(&data)?,
+    _ => data.into_bytes(),
+  };
+  Ok(bytes_to_hex(&crc16(&bytes).to_le_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::serial_io::MockSerial;
+
+  #[test]
+  fn exception_response_decodes_to_typed_error() {
+    let mut reply = vec![0x01, 0x83, 0x02];
+    reply.extend_from_slice(&crc16(&reply).to_le_bytes());
+    let mut port = MockSerial::new(&reply);
+
+    let request = [0x03, 0x00, 0x10, 0x00, 0x01];
+    let timeout = Duration::from_millis(50);
+    let err = rtu_transaction(&mut port, 1, &request, timeout, None).unwrap_err();
+    let ModbusError::Exception { function, exception } = err else {
+      panic!("expected exception, got {err}");
+    };
+    assert_eq!(function, 0x03);
+    assert_eq!(exception, ModbusException::IllegalDataAddress);
+    assert_eq!(exception.code(), 0x02);
+  }
+
+  #[test]
+  fn manual_driver_enable_raises_rts_only_while_sending() {
+    let request = [0x03, 0x00, 0x00, 0x00, 0x01];
+    let mut reply = vec![0x01, 0x03, 0x02, 0x00, 0x2A];
+    reply.extend_from_slice(&crc16(&reply).to_le_bytes());
+    let mut port = MockSerial::new(&reply);
+    let de = Some(DriverEnable::Manual {
+      active_high: true,
+      post_tx_delay_us: 0,
+    });
+
+    let pdu = rtu_transaction(&mut port, 1, &request, Duration::from_millis(50), de).unwrap();
+    assert_eq!(pdu, [0x03, 0x02, 0x00, 0x2A]);
+    assert!(!port.rts_at_write.is_empty());
+    assert!(port.rts_at_write.iter().all(|rts| *rts));
+    assert!(!port.rts);
+  }
+
+  #[test]
+  fn ascii_round_trip_matches_canonical_frame() {
+    // Read 10 holding registers from slave 1: the Modbus ASCII spec example.
+    let request = [0x03, 0x00, 0x00, 0x00, 0x0A];
+    assert_eq!(ascii_frame(1, &request), b":01030000000AF2\r\n");
+
+    let reply = b":0103040001000AED\r\n";
+    let mut port = MockSerial::new(reply);
+    let request = [0x03, 0x00, 0x00, 0x00, 0x02];
+    let timeout = Duration::from_millis(50);
+    let de = Some(DriverEnable::Manual {
+      active_high: true,
+      post_tx_delay_us: 0,
+    });
+    let pdu = ascii_transaction(&mut port, 1, &request, timeout, de).unwrap();
+    assert_eq!(pdu, [0x03, 0x04, 0x00, 0x01, 0x00, 0x0A]);
+    assert_eq!(port.written, ascii_frame(1, &request));
+    assert!(port.rts_at_write.iter().all(|rts| *rts));
+    assert!(!port.rts);
+
+    let mut port = MockSerial::new(b":018302\r\n");
+    assert!(matches!(
+      ascii_transaction(&mut port, 1, &request, timeout, None),
+      Err(ModbusError::InvalidResponse(_))
+    ));
+    let mut port = MockSerial::new(b":0183027A\r\n");
+    let err = ascii_transaction(&mut port, 1, &request, timeout, None).unwrap_err();
+    assert!(matches!(
+      err,
+      ModbusError::Exception { function: 0x03, exception: ModbusException::IllegalDataAddress }
+    ));
+  }
+
+  #[test]
+  fn frame_silence_is_three_and_a_half_characters() {
+    assert_eq!(frame_silence(9600), Duration::from_micros(4010));
+    assert_eq!(frame_silence(19_200), Duration::from_micros(2005));
+    assert_eq!(frame_silence(38_400), Duration::from_micros(1750));
+    assert_eq!(frame_silence(115_200), Duration::from_micros(1750));
+  }
+
+  #[test]
+  fn crc16_matches_canonical_read_request() {
+    let crc = modbus_crc16("01 03 00 00 00 0A".to_string(), Some("hex".to_string())).unwrap();
+    assert_eq!(crc, "C5 CD");
+  }
+}
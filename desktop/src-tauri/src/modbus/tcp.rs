@@ -0,0 +1,250 @@
+//! Modbus TCP client commands for devices on Ethernet.
+//! Sends the same PDUs as the serial path behind an MBAP header, one connection per request.
+
+use std::{
+  io::{self, ErrorKind, Read, Write},
+  net::{TcpStream, ToSocketAddrs},
+  sync::atomic::{AtomicU16, Ordering},
+  time::Duration,
+};
+
+use super::{
+  check_response_pdu, check_write_echo, decode_coils, decode_registers, invalid,
+  read_coils_pdu, read_holding_registers_pdu, write_multiple_registers_pdu,
+  write_single_coil_pdu, write_single_register_pdu, ModbusError,
+};
+use crate::serial::SerialError;
+
+/// Default Modbus TCP port, used when `port` is omitted.
+const DEFAULT_PORT: u16 = 502;
+/// Transaction id (2) + protocol id (2) + length (2) + unit id (1).
+const MBAP_HEADER_LEN: usize = 7;
+/// Largest PDU an MBAP length field may announce, plus the unit id byte.
+const MAX_MBAP_LENGTH: usize = 254;
+
+/// Transaction ids for outgoing requests; replies must echo them.
+static NEXT_TRANSACTION: AtomicU16 = AtomicU16::new(1);
+
+/// Sockets report an elapsed read timeout as `WouldBlock` on Unix.
+fn io_error(err: io::Error) -> ModbusError {
+  match err.kind() {
+    ErrorKind::WouldBlock | ErrorKind::TimedOut => SerialError::Timeout.into(),
+    _ => err.into(),
+  }
+}
+
+fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, ModbusError> {
+  let host = host.trim();
+  if host.is_empty() {
+    return Err(SerialError::InvalidInput("Host must not be empty".to_string()).into());
+  }
+  let addrs = (host, port)
+    .to_socket_addrs()
+    .map_err(|err| SerialError::InvalidInput(format!("Cannot resolve {host}: {err}")))?;
+  let mut last_err = None;
+  for addr in addrs {
+    match TcpStream::connect_timeout(&addr, timeout) {
+      Ok(stream) => {
+        stream.set_read_timeout(Some(timeout)).map_err(io_error)?;
+        stream.set_write_timeout(Some(timeout)).map_err(io_error)?;
+        stream.set_nodelay(true).map_err(io_error)?;
+        return Ok(stream);
+      }
+      Err(err) => last_err = Some(err),
+    }
+  }
+  Err(match last_err {
+    Some(err) => io_error(err),
+    None => SerialError::InvalidInput(format!("No address found for {host}")).into(),
+  })
+}
+
+/// MBAP header followed by `pdu`.
+fn mbap_frame(transaction: u16, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+  let mut frame = Vec::with_capacity(MBAP_HEADER_LEN + pdu.len());
+  frame.extend_from_slice(&transaction.to_be_bytes());
+  frame.extend_from_slice(&0u16.to_be_bytes());
+  frame.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+  frame.push(unit_id);
+  frame.extend_from_slice(pdu);
+  frame
+}
+
+/// Sends one request and returns the response PDU after checking that the
+/// header echoes the transaction and unit ids.
+fn tcp_transaction(
+  stream: &mut (impl Read + Write),
+  unit_id: u8,
+  pdu: &[u8],
+) -> Result<Vec<u8>, ModbusError> {
+  let transaction = NEXT_TRANSACTION.fetch_add(1, Ordering::Relaxed);
+  stream
+    .write_all(&mbap_frame(transaction, unit_id, pdu))
+    .map_err(io_error)?;
+  stream.flush().map_err(io_error)?;
+
+  let mut header = [0u8; MBAP_HEADER_LEN];
+  stream.read_exact(&mut header).map_err(io_error)?;
+  let received = u16::from_be_bytes([header[0], header[1]]);
+  if received != transaction {
+    return Err(invalid(format!("expected transaction {transaction}, got {received}")));
+  }
+  let protocol = u16::from_be_bytes([header[2], header[3]]);
+  if protocol != 0 {
+    return Err(invalid(format!("unexpected protocol id {protocol}")));
+  }
+  let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+  if !(2..=MAX_MBAP_LENGTH).contains(&length) {
+    return Err(invalid(format!("invalid MBAP length {length}")));
+  }
+  if header[6] != unit_id {
+    return Err(invalid(format!("expected unit {unit_id}, got {}", header[6])));
+  }
+
+  let mut response = vec![0u8; length - 1];
+  stream.read_exact(&mut response).map_err(io_error)?;
+  check_response_pdu(pdu[0], &response)
+}
+
+/// Connects, runs one transaction and closes the connection.
+fn request(
+  host: &str,
+  port: Option<u16>,
+  unit_id: u8,
+  pdu: &[u8],
+  timeout_ms: u64,
+) -> Result<Vec<u8>, ModbusError> {
+  let timeout = Duration::from_millis(timeout_ms.max(1));
+  let mut stream = connect(host, port.unwrap_or(DEFAULT_PORT), timeout)?;
+  tcp_transaction(&mut stream, unit_id, pdu)
+}
+
+#[tauri::command]
+pub fn modbus_tcp_read_holding_registers(
+  host: String,
+  port: Option<u16>,
+  unit_id: u8,
+  start: u16,
+  count: u16,
+  timeout_ms: u64,
+) -> Result<Vec<u16>, ModbusError> {
+  let pdu = read_holding_registers_pdu(start, count)?;
+  let response = request(&host, port, unit_id, &pdu, timeout_ms)?;
+  let registers = decode_registers(&response, count)?;
+  eprintln!("[modbus] tcp read holding ok host={host} unit={unit_id} start={start} count={count}");
+  Ok(registers)
+}
+
+#[tauri::command]
+pub fn modbus_tcp_read_coils(
+  host: String,
+  port: Option<u16>,
+  unit_id: u8,
+  start: u16,
+  count: u16,
+  timeout_ms: u64,
+) -> Result<Vec<bool>, ModbusError> {
+  let pdu = read_coils_pdu(start, count)?;
+  let response = request(&host, port, unit_id, &pdu, timeout_ms)?;
+  let coils = decode_coils(&response, count)?;
+  eprintln!("[modbus] tcp read coils ok host={host} unit={unit_id} start={start} count={count}");
+  Ok(coils)
+}
+
+#[tauri::command]
+pub fn modbus_tcp_write_single_register(
+  host: String,
+  port: Option<u16>,
+  unit_id: u8,
+  addr: u16,
+  value: u16,
+  timeout_ms: u64,
+) -> Result<(), ModbusError> {
+  let pdu = write_single_register_pdu(addr, value);
+  let response = request(&host, port, unit_id, &pdu, timeout_ms)?;
+  check_write_echo(&pdu, &response)?;
+  eprintln!("[modbus] tcp write single ok host={host} unit={unit_id} addr={addr} value={value}");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn modbus_tcp_write_multiple_registers(
+  host: String,
+  port: Option<u16>,
+  unit_id: u8,
+  start: u16,
+  values: Vec<u16>,
+  timeout_ms: u64,
+) -> Result<(), ModbusError> {
+  let pdu = write_multiple_registers_pdu(start, &values)?;
+  let response = request(&host, port, unit_id, &pdu, timeout_ms)?;
+  check_write_echo(&pdu, &response)?;
+  let count = values.len();
+  eprintln!(
+    "[modbus] tcp write multiple ok host={host} unit={unit_id} start={start} count={count}"
+  );
+  Ok(())
+}
+
+#[tauri::command]
+pub fn modbus_tcp_write_single_coil(
+  host: String,
+  port: Option<u16>,
+  unit_id: u8,
+  addr: u16,
+  on: bool,
+  timeout_ms: u64,
+) -> Result<(), ModbusError> {
+  let pdu = write_single_coil_pdu(addr, on);
+  let response = request(&host, port, unit_id, &pdu, timeout_ms)?;
+  check_write_echo(&pdu, &response)?;
+  eprintln!("[modbus] tcp write coil ok host={host} unit={unit_id} addr={addr} on={on}");
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{net::TcpListener, thread};
+
+  use super::*;
+  use crate::modbus::ModbusException;
+
+  /// Answers one 12-byte request on a local socket with `reply(request)`.
+  fn serve_once(reply: impl FnOnce(&[u8]) -> Vec<u8> + Send + 'static) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = [0u8; 12];
+      stream.read_exact(&mut request).unwrap();
+      stream.write_all(&reply(&request)).unwrap();
+    });
+    port
+  }
+
+  #[test]
+  fn reads_registers_and_decodes_exceptions_over_mbap() {
+    let port = serve_once(|request| {
+      // Read 2 holding registers from 0x006B on unit 17.
+      assert_eq!(&request[2..], [0, 0, 0, 6, 17, 0x03, 0x00, 0x6B, 0x00, 0x02]);
+      let mut reply = request[..2].to_vec();
+      reply.extend_from_slice(&[0, 0, 0, 7, 17, 0x03, 0x04, 0x02, 0x2B, 0x00, 0x64]);
+      reply
+    });
+    let registers =
+      modbus_tcp_read_holding_registers("127.0.0.1".into(), Some(port), 17, 0x6B, 2, 500);
+    assert_eq!(registers.unwrap(), [0x022B, 0x0064]);
+
+    let port = serve_once(|request| {
+      let mut reply = request[..2].to_vec();
+      reply.extend_from_slice(&[0, 0, 0, 3, 17, 0x83, 0x02]);
+      reply
+    });
+    let err = modbus_tcp_read_holding_registers("127.0.0.1".into(), Some(port), 17, 0x6B, 2, 500)
+      .unwrap_err();
+    assert!(matches!(
+      err,
+      ModbusError::Exception { function: 0x03, exception: ModbusException::IllegalDataAddress }
+    ));
+  }
+}
@@ -0,0 +1,207 @@
+//! Background Modbus polling for dashboards.
+//! Reads configured register/coil ranges on a cadence and emits telemetry samples.
+
+use std::{
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::api_server::{TelemetryBus, TelemetryEvent};
+use crate::modbus::{
+  read_coils, read_holding_registers, ModbusError, ModbusMode, FC_READ_COILS,
+  FC_READ_HOLDING_REGISTERS,
+};
+use crate::serial::{PortEntry, SerialError, SerialState, SerialStream};
+
+/// Shortest interval a job may poll at.
+const MIN_POLL_INTERVAL_MS: u64 = 50;
+/// Longest interval a job may poll at (one day).
+const MAX_POLL_INTERVAL_MS: u64 = 86_400_000;
+/// Upper bound on the offset between consecutive jobs' first reads.
+const MAX_STAGGER_MS: u64 = 250;
+
+#[derive(Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollJob {
+  pub slave: u8,
+  /// `0x01` (coils) or `0x03` (holding registers).
+  pub function: u8,
+  pub start: u16,
+  pub count: u16,
+  pub interval_ms: u64,
+  pub metric: String,
+  #[serde(default = "default_poll_timeout_ms")]
+  pub timeout_ms: u64,
+  #[serde(default)]
+  pub modbus_mode: ModbusMode,
+  /// Tags published samples so `/ws/realtime` clients can attribute them.
+  #[serde(default)]
+  pub device_uid: Option<String>,
+  /// Also push samples to the embedded API server's realtime broadcast.
+  #[serde(default)]
+  pub publish: bool,
+}
+
+fn default_poll_timeout_ms() -> u64 {
+  500
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollError {
+  pub port_id: String,
+  pub metric: String,
+  pub error: ModbusError,
+}
+
+fn poll_once(state: &PortEntry, job: &PollJob) -> Result<Value, ModbusError> {
+  let (slave, start, count, timeout_ms) = (job.slave, job.start, job.count, job.timeout_ms);
+  let value = match job.function {
+    FC_READ_HOLDING_REGISTERS => json!(read_holding_registers(
+      state,
+      slave,
+      start,
+      count,
+      timeout_ms,
+      job.modbus_mode
+    )?),
+    FC_READ_COILS => json!(read_coils(state, slave, start, count, timeout_ms, job.modbus_mode)?),
+    _ => unreachable!("validated in start_modbus_poll"),
+  };
+  // Single-point jobs publish a scalar instead of a one-element array.
+  Ok(match value {
+    Value::Array(mut items) if items.len() == 1 => items.remove(0),
+    other => other,
+  })
+}
+
+/// Single scheduler thread: the bus is half-duplex, so jobs are serviced one
+/// at a time in due-time order. A job whose next slot has already passed
+/// (because another request ran long) skips those cycles instead of bursting.
+fn run_poll<R: Runtime>(
+  app: AppHandle<R>,
+  state: Arc<PortEntry>,
+  stop: Arc<AtomicBool>,
+  jobs: Vec<PollJob>,
+) {
+  let stagger = Duration::from_millis(MAX_STAGGER_MS.min(
+    jobs.iter().map(|job| job.interval_ms).min().unwrap_or(0) / jobs.len().max(1) as u64,
+  ));
+  let now = Instant::now();
+  let mut due: Vec<Instant> = (0..jobs.len()).map(|i| now + stagger * i as u32).collect();
+
+  while !stop.load(Ordering::Relaxed) {
+    let Some((index, &next)) = due.iter().enumerate().min_by_key(|(_, at)| **at) else {
+      break;
+    };
+    let now = Instant::now();
+    if next > now {
+      thread::sleep((next - now).min(Duration::from_millis(50)));
+      continue;
+    }
+
+    let job = &jobs[index];
+    match poll_once(&state, job) {
+      Ok(value) => {
+        let event = TelemetryEvent {
+          ts: chrono::Utc::now().to_rfc3339(),
+          device_id: None,
+          device_uid: job.device_uid.clone(),
+          metrics: json!({ job.metric.clone(): value }),
+          quality: None,
+        };
+        if job.publish {
+          if let Some(bus) = app.try_state::<TelemetryBus>() {
+            bus.publish(event.clone());
+          }
+        }
+        let _ = app.emit("telemetry:sample", event);
+      }
+      Err(err) => {
+        eprintln!("[modbus] poll {} failed: {err}", job.metric);
+        let _ = app.emit(
+          "modbus:poll_error",
+          PollError {
+            port_id: state.id.clone(),
+            metric: job.metric.clone(),
+            error: err,
+          },
+        );
+      }
+    }
+
+    let interval = Duration::from_millis(job.interval_ms);
+    let finished = Instant::now();
+    let mut next = due[index] + interval;
+    if next <= finished {
+      let skipped = ((finished - next).as_millis() / interval.as_millis().max(1)) + 1;
+      eprintln!("[modbus] poll {} overran, skipping {skipped} cycle(s)", job.metric);
+      next = finished + interval;
+    }
+    due[index] = next;
+  }
+}
+
+/// Starts polling `jobs` on `port_id`, replacing any schedule running there.
+/// Each success emits `telemetry:sample`; failures emit `modbus:poll_error`
+/// and polling continues.
+#[tauri::command]
+pub fn start_modbus_poll<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+  jobs: Vec<PollJob>,
+) -> Result<(), SerialError> {
+  if jobs.is_empty() {
+    return Err(SerialError::InvalidInput("At least one poll job is required".to_string()));
+  }
+  for job in &jobs {
+    if job.function != FC_READ_HOLDING_REGISTERS && job.function != FC_READ_COILS {
+      return Err(SerialError::InvalidInput(format!(
+        "Unsupported poll function 0x{:02X} for {}",
+        job.function, job.metric
+      )));
+    }
+    if !(MIN_POLL_INTERVAL_MS..=MAX_POLL_INTERVAL_MS).contains(&job.interval_ms) {
+      return Err(SerialError::InvalidInput(format!(
+        "Poll interval for {} must be between {MIN_POLL_INTERVAL_MS} and \
+         {MAX_POLL_INTERVAL_MS} ms",
+        job.metric
+      )));
+    }
+  }
+
+  let state = state.entry(port_id.as_deref())?;
+  stop_poll(&state)?;
+  let count = jobs.len();
+  let entry = state.clone();
+  *state.modbus_poll.lock()? =
+    Some(SerialStream::spawn(move |stop| run_poll(app, entry, stop, jobs)));
+  eprintln!("[modbus] poll started port_id={} jobs={count}", state.id);
+  Ok(())
+}
+
+pub(crate) fn stop_poll(state: &PortEntry) -> Result<(), SerialError> {
+  let poll = state.modbus_poll.lock()?.take();
+  if let Some(poll) = poll {
+    poll.stop();
+    eprintln!("[modbus] poll stopped port_id={}", state.id);
+  }
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_modbus_poll(
+  state: State<SerialState>,
+  port_id: Option<String>,
+) -> Result<(), SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  stop_poll(&state)
+}
@@ -0,0 +1,248 @@
+//! Network interface enumeration for the system diagnostics panel.
+//! Uses `getifaddrs` on Unix and `GetAdaptersAddresses` on Windows.
+
+use std::{
+  collections::BTreeMap,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetIf {
+  pub name: String,
+  /// Hardware address as `aa:bb:cc:dd:ee:ff`, when the OS reports one.
+  pub mac: Option<String>,
+  pub ipv4: Vec<String>,
+  pub ipv6: Vec<String>,
+  pub is_loopback: bool,
+}
+
+impl NetIf {
+  fn push_addr(&mut self, addr: IpAddr) {
+    match addr {
+      IpAddr::V4(addr) => self.ipv4.push(addr.to_string()),
+      IpAddr::V6(addr) => self.ipv6.push(addr.to_string()),
+    }
+  }
+}
+
+fn format_mac(bytes: &[u8]) -> Option<String> {
+  if bytes.is_empty() || bytes.iter().all(|byte| *byte == 0) {
+    return None;
+  }
+  let parts: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+  Some(parts.join(":"))
+}
+
+#[cfg(unix)]
+fn collect_interfaces() -> Vec<NetIf> {
+  use std::ffi::CStr;
+
+  let mut by_name: BTreeMap<String, NetIf> = BTreeMap::new();
+  let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+  // SAFETY: on success `head` owns a list released by `freeifaddrs` below.
+  if unsafe { libc::getifaddrs(&mut head) } != 0 {
+    eprintln!("[system] getifaddrs failed: {}", std::io::Error::last_os_error());
+    return Vec::new();
+  }
+  let mut cursor = head;
+  while !cursor.is_null() {
+    // SAFETY: `cursor` walks the list returned by getifaddrs, which stays
+    // valid until freeifaddrs; each address is read as its family's type.
+    unsafe {
+      let entry = &*cursor;
+      cursor = entry.ifa_next;
+      let name = CStr::from_ptr(entry.ifa_name).to_string_lossy().into_owned();
+      let netif = by_name.entry(name.clone()).or_insert_with(|| NetIf {
+        name,
+        ..NetIf::default()
+      });
+      netif.is_loopback |= entry.ifa_flags & (libc::IFF_LOOPBACK as libc::c_uint) != 0;
+      if entry.ifa_addr.is_null() {
+        continue;
+      }
+      match i32::from((*entry.ifa_addr).sa_family) {
+        libc::AF_INET => {
+          let addr = &*(entry.ifa_addr as *const libc::sockaddr_in);
+          netif.push_addr(IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr))));
+        }
+        libc::AF_INET6 => {
+          let addr = &*(entry.ifa_addr as *const libc::sockaddr_in6);
+          netif.push_addr(IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr)));
+        }
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        libc::AF_PACKET => {
+          let addr = &*(entry.ifa_addr as *const libc::sockaddr_ll);
+          let len = usize::from(addr.sll_halen).min(addr.sll_addr.len());
+          netif.mac = netif.mac.take().or_else(|| format_mac(&addr.sll_addr[..len]));
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+        libc::AF_LINK => {
+          let addr = &*(entry.ifa_addr as *const libc::sockaddr_dl);
+          let data = std::slice::from_raw_parts(
+            addr.sdl_data.as_ptr() as *const u8,
+            usize::from(addr.sdl_nlen) + usize::from(addr.sdl_alen),
+          );
+          let mac = format_mac(&data[usize::from(addr.sdl_nlen)..]);
+          netif.mac = netif.mac.take().or(mac);
+        }
+        _ => {}
+      }
+    }
+  }
+  // SAFETY: `head` came from a successful getifaddrs and is freed once.
+  unsafe { libc::freeifaddrs(head) };
+  by_name.into_values().collect()
+}
+
+#[cfg(windows)]
+mod win {
+  //! Prefixes of the iphlpapi structs; only the leading fields are read.
+
+  pub const AF_UNSPEC: u32 = 0;
+  pub const AF_INET: u16 = 2;
+  pub const AF_INET6: u16 = 23;
+  pub const GAA_FLAG_SKIP_ANYCAST: u32 = 0x2;
+  pub const GAA_FLAG_SKIP_MULTICAST: u32 = 0x4;
+  pub const GAA_FLAG_SKIP_DNS_SERVER: u32 = 0x8;
+  pub const ERROR_SUCCESS: u32 = 0;
+  pub const ERROR_BUFFER_OVERFLOW: u32 = 111;
+  pub const IF_TYPE_SOFTWARE_LOOPBACK: u32 = 24;
+
+  #[repr(C)]
+  pub struct SocketAddress {
+    pub sockaddr: *const u8,
+    pub sockaddr_len: i32,
+  }
+
+  #[repr(C)]
+  pub struct UnicastAddress {
+    pub alignment: u64,
+    pub next: *const UnicastAddress,
+    pub address: SocketAddress,
+  }
+
+  #[repr(C)]
+  pub struct AdapterAddresses {
+    pub alignment: u64,
+    pub next: *const AdapterAddresses,
+    pub adapter_name: *const u8,
+    pub first_unicast: *const UnicastAddress,
+    pub first_anycast: *const u8,
+    pub first_multicast: *const u8,
+    pub first_dns_server: *const u8,
+    pub dns_suffix: *const u16,
+    pub description: *const u16,
+    pub friendly_name: *const u16,
+    pub physical_address: [u8; 8],
+    pub physical_address_len: u32,
+    pub flags: u32,
+    pub mtu: u32,
+    pub if_type: u32,
+  }
+
+  #[link(name = "iphlpapi")]
+  extern "system" {
+    pub fn GetAdaptersAddresses(
+      family: u32,
+      flags: u32,
+      reserved: *mut u8,
+      addresses: *mut AdapterAddresses,
+      size: *mut u32,
+    ) -> u32;
+  }
+}
+
+#[cfg(windows)]
+fn collect_interfaces() -> Vec<NetIf> {
+  let flags =
+    win::GAA_FLAG_SKIP_ANYCAST | win::GAA_FLAG_SKIP_MULTICAST | win::GAA_FLAG_SKIP_DNS_SERVER;
+  // u64 storage keeps the buffer aligned for the adapter structs.
+  let mut size: u32 = 16 * 1024;
+  let mut buf: Vec<u64>;
+  loop {
+    buf = vec![0; (size as usize).div_ceil(8)];
+    let addresses = buf.as_mut_ptr() as *mut win::AdapterAddresses;
+    // SAFETY: `buf` holds at least `size` writable, aligned bytes.
+    let rc = unsafe {
+      win::GetAdaptersAddresses(win::AF_UNSPEC, flags, std::ptr::null_mut(), addresses, &mut size)
+    };
+    match rc {
+      win::ERROR_SUCCESS => break,
+      win::ERROR_BUFFER_OVERFLOW => continue,
+      rc => {
+        let err = std::io::Error::from_raw_os_error(rc as i32);
+        eprintln!("[system] GetAdaptersAddresses failed: {err}");
+        return Vec::new();
+      }
+    }
+  }
+
+  let wide = |ptr: *const u16| {
+    if ptr.is_null() {
+      return String::new();
+    }
+    // SAFETY: iphlpapi strings are NUL-terminated and live as long as `buf`.
+    unsafe {
+      let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+      String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+    }
+  };
+
+  let mut by_name: BTreeMap<String, NetIf> = BTreeMap::new();
+  let mut adapter = buf.as_ptr() as *const win::AdapterAddresses;
+  while !adapter.is_null() {
+    // SAFETY: the adapter and unicast lists point into `buf`, filled above.
+    unsafe {
+      let entry = &*adapter;
+      adapter = entry.next;
+      let name = wide(entry.friendly_name);
+      let len = (entry.physical_address_len as usize).min(entry.physical_address.len());
+      let netif = by_name.entry(name.clone()).or_insert_with(|| NetIf {
+        name,
+        mac: format_mac(&entry.physical_address[..len]),
+        is_loopback: entry.if_type == win::IF_TYPE_SOFTWARE_LOOPBACK,
+        ..NetIf::default()
+      });
+      let mut unicast = entry.first_unicast;
+      while !unicast.is_null() {
+        let address = &(*unicast).address;
+        unicast = (*unicast).next;
+        if address.sockaddr.is_null() {
+          continue;
+        }
+        let family = u16::from_ne_bytes([*address.sockaddr, *address.sockaddr.add(1)]);
+        let ip = match family {
+          win::AF_INET => {
+            let octets = std::slice::from_raw_parts(address.sockaddr.add(4), 4);
+            IpAddr::V4(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+          }
+          win::AF_INET6 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(std::slice::from_raw_parts(address.sockaddr.add(8), 16));
+            IpAddr::V6(Ipv6Addr::from(octets))
+          }
+          _ => continue,
+        };
+        netif.push_addr(ip);
+      }
+    }
+  }
+  by_name.into_values().collect()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn collect_interfaces() -> Vec<NetIf> {
+  Vec::new()
+}
+
+/// Interfaces with their MAC and IP addresses; loopback is left out unless
+/// `include_loopback` is set.
+#[tauri::command]
+pub fn network_interfaces(include_loopback: Option<bool>) -> Vec<NetIf> {
+  let include_loopback = include_loopback.unwrap_or(false);
+  collect_interfaces()
+    .into_iter()
+    .filter(|netif| include_loopback || !netif.is_loopback)
+    .collect()
+}
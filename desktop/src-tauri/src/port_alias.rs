@@ -0,0 +1,160 @@
+//! Friendly names for serial ports that survive re-enumeration.
+//! Resolved to a concrete path when a port is opened.
+
+use std::collections::BTreeMap;
+
+use tauri::{AppHandle, Runtime};
+
+use crate::json_store::{self, JsonStore};
+use crate::serial::{list_serial_ports_detailed, SerialError};
+
+static ALIASES: JsonStore = JsonStore::new("port_aliases.json", "aliases");
+
+/// What an alias points at: a fixed path, or a USB device found by its IDs
+/// wherever it enumerates (`{ "usb": { "vid": 1027, "pid": 24577 } }`).
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortMatch {
+  Path(String),
+  #[serde(rename_all = "camelCase")]
+  Usb {
+    vid: u16,
+    pid: u16,
+    /// Needed to tell apart two adapters of the same model.
+    #[serde(default)]
+    serial_number: Option<String>,
+  },
+}
+
+type Aliases = BTreeMap<String, PortMatch>;
+
+fn validate_alias(alias: &str) -> Result<&str, SerialError> {
+  json_store::validate_name("alias", alias)
+}
+
+/// `open_serial_port` tries aliases before paths, so an alias must not be a
+/// port name. Unix port names are absolute paths, which `validate_name`
+/// already rules out; on Windows that means `COM<n>` or a listed port.
+fn check_not_port_name(alias: &str) -> Result<(), SerialError> {
+  let com_port = alias.get(..3).is_some_and(|prefix| prefix.eq_ignore_ascii_case("com"))
+    && alias.get(3..).is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+  let listed = || list_serial_ports_detailed().iter().any(|port| port.name == alias);
+  if com_port || listed() {
+    return Err(SerialError::InvalidInput(format!(
+      "Alias \"{alias}\" is a serial port name; pick a name that isn't one"
+    )));
+  }
+  Ok(())
+}
+
+/// Current path of the port `target` describes.
+fn find_port(alias: &str, target: &PortMatch) -> Result<String, SerialError> {
+  let (vid, pid, serial_number) = match target {
+    PortMatch::Path(path) => return Ok(path.clone()),
+    PortMatch::Usb {
+      vid,
+      pid,
+      serial_number,
+    } => (*vid, *pid, serial_number.as_deref()),
+  };
+  let matches: Vec<String> = list_serial_ports_detailed()
+    .into_iter()
+    .filter(|port| port.vid == Some(vid) && port.pid == Some(pid))
+    .filter(|port| serial_number.is_none() || port.serial_number.as_deref() == serial_number)
+    .map(|port| port.name)
+    .collect();
+  match matches.as_slice() {
+    [path] => Ok(path.clone()),
+    [] => Err(SerialError::InvalidConfig(format!(
+      "No connected device matches alias \"{alias}\" (USB {vid:04X}:{pid:04X})"
+    ))),
+    paths => Err(SerialError::InvalidConfig(format!(
+      "Alias \"{alias}\" matches several devices ({}); add a serial number to it",
+      paths.join(", ")
+    ))),
+  }
+}
+
+/// Resolves `port` to a concrete path when it names an alias; `None` means
+/// it isn't one and should be opened as given.
+pub(crate) fn resolve_port_alias<R: Runtime>(
+  app: &AppHandle<R>,
+  port: &str,
+) -> Result<Option<String>, SerialError> {
+  let path = ALIASES.path(app)?;
+  let target = {
+    let _guard = ALIASES.lock()?;
+    ALIASES.read::<Aliases>(&path).remove(port.trim())
+  };
+  let Some(target) = target else {
+    return Ok(None);
+  };
+  let resolved = find_port(port, &target)?;
+  eprintln!("[serial] alias resolved alias={port} port={resolved}");
+  Ok(Some(resolved))
+}
+
+/// Saves `alias` (replacing any previous target), so `open_serial_port` can
+/// be given the alias instead of a path.
+#[tauri::command]
+pub fn set_port_alias<R: Runtime>(
+  app: AppHandle<R>,
+  alias: String,
+  identifier: PortMatch,
+) -> Result<(), SerialError> {
+  let alias = validate_alias(&alias)?;
+  check_not_port_name(alias)?;
+  if let PortMatch::Path(path) = &identifier {
+    if path.trim().is_empty() {
+      return Err(SerialError::InvalidInput("Alias path must not be empty".to_string()));
+    }
+  }
+  let path = ALIASES.path(&app)?;
+  let _guard = ALIASES.lock()?;
+  let mut aliases: Aliases = ALIASES.read(&path);
+  aliases.insert(alias.to_string(), identifier);
+  ALIASES.write(&path, &aliases)?;
+  eprintln!("[serial] alias saved alias={alias}");
+  Ok(())
+}
+
+/// Returns whether an alias was removed.
+#[tauri::command]
+pub fn remove_port_alias<R: Runtime>(app: AppHandle<R>, alias: String) -> Result<bool, SerialError> {
+  let alias = validate_alias(&alias)?;
+  let path = ALIASES.path(&app)?;
+  let _guard = ALIASES.lock()?;
+  let mut aliases: Aliases = ALIASES.read(&path);
+  if aliases.remove(alias).is_none() {
+    return Ok(false);
+  }
+  ALIASES.write(&path, &aliases)?;
+  eprintln!("[serial] alias removed alias={alias}");
+  Ok(true)
+}
+
+/// Saved aliases in alphabetical order.
+#[tauri::command]
+pub fn list_port_aliases<R: Runtime>(
+  app: AppHandle<R>,
+) -> Result<BTreeMap<String, PortMatch>, SerialError> {
+  let path = ALIASES.path(&app)?;
+  let _guard = ALIASES.lock()?;
+  Ok(ALIASES.read(&path))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn aliases_cannot_shadow_com_ports() {
+    for name in ["COM3", "com12"] {
+      assert!(check_not_port_name(name).is_err(), "{name}");
+    }
+    for name in ["COM", "Compressor", "COM3 meter"] {
+      assert!(check_not_port_name(name).is_ok(), "{name}");
+    }
+    assert!(validate_alias("../ports").is_err());
+  }
+}
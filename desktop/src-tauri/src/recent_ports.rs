@@ -0,0 +1,90 @@
+//! Most-recently-opened serial ports, newest first, for the Recent Ports menu.
+//! Each entry keeps the config it was opened with, so it reopens as before.
+
+use tauri::{AppHandle, Runtime};
+
+use crate::json_store::JsonStore;
+use crate::serial::{SerialConfig, SerialError};
+
+pub const MAX_RECENT_PORTS: usize = 8;
+
+static RECENT: JsonStore = JsonStore::new("recent_ports.json", "recent ports");
+
+/// A port as last opened, so the frontend can reopen it with the same settings.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentPort {
+  pub port: String,
+  /// USB product name when the OS reports one.
+  pub label: Option<String>,
+  pub config: SerialConfig,
+}
+
+impl RecentPort {
+  pub fn menu_text(&self) -> String {
+    match &self.label {
+      Some(label) => format!("{} ({})", self.port, label),
+      None => self.port.clone(),
+    }
+  }
+}
+
+fn port_label(port: &str) -> Option<String> {
+  serialport::available_ports()
+    .ok()?
+    .into_iter()
+    .find(|info| info.port_name == port)
+    .and_then(|info| match info.port_type {
+      serialport::SerialPortType::UsbPort(usb) => usb.product,
+      _ => None,
+    })
+}
+
+/// Recent ports, newest first; empty when the list can't be read.
+pub fn recent_ports<R: Runtime>(app: &AppHandle<R>) -> Vec<RecentPort> {
+  let Ok(path) = RECENT.path(app) else {
+    return Vec::new();
+  };
+  let Ok(_guard) = RECENT.lock() else {
+    return Vec::new();
+  };
+  RECENT.read(&path)
+}
+
+/// Moves `config.port` to the front of the list, replacing any older entry
+/// for the same port and dropping anything past `MAX_RECENT_PORTS`.
+pub fn record_recent_port<R: Runtime>(
+  app: &AppHandle<R>,
+  config: &SerialConfig,
+) -> Result<(), SerialError> {
+  let path = RECENT.path(app)?;
+  let _guard = RECENT.lock()?;
+  let mut recent: Vec<RecentPort> = RECENT.read(&path);
+  recent.retain(|entry| entry.port != config.port);
+  recent.insert(
+    0,
+    RecentPort {
+      port: config.port.clone(),
+      label: port_label(&config.port),
+      config: config.clone(),
+    },
+  );
+  recent.truncate(MAX_RECENT_PORTS);
+  RECENT.write(&path, &recent)
+}
+
+#[tauri::command]
+pub fn list_recent_ports<R: Runtime>(app: AppHandle<R>) -> Vec<RecentPort> {
+  recent_ports(&app)
+}
+
+#[tauri::command]
+pub fn clear_recent_ports<R: Runtime>(app: AppHandle<R>) -> Result<(), SerialError> {
+  let path = RECENT.path(&app)?;
+  {
+    let _guard = RECENT.lock()?;
+    RECENT.write::<[RecentPort]>(&path, &[])?;
+  }
+  crate::menu::refresh_menus(&app);
+  Ok(())
+}
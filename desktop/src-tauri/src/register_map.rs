@@ -0,0 +1,225 @@
+//! Named Modbus register maps with scaling and units.
+//! Decodes raw holding registers into engineering values for the UI.
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::Mutex,
+};
+
+use tauri::State;
+
+use crate::modbus::{read_holding_registers, ModbusError, ModbusMode};
+use crate::serial::{SerialError, SerialState};
+
+/// Registers per read request when a map spans a wide range.
+const MAX_CHUNK_REGISTERS: u16 = 125;
+/// First address of the 4xxxx holding-register reference notation.
+const HOLDING_REFERENCE_BASE: u32 = 40_001;
+
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegisterType {
+  U16,
+  I16,
+  U32,
+  I32,
+  F32,
+}
+
+impl RegisterType {
+  fn width(self) -> u16 {
+    match self {
+      RegisterType::U16 | RegisterType::I16 => 1,
+      RegisterType::U32 | RegisterType::I32 | RegisterType::F32 => 2,
+    }
+  }
+}
+
+/// Which register holds the high word of a 32-bit value.
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordOrder {
+  /// High word first (Modicon convention).
+  #[default]
+  Big,
+  Little,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterDef {
+  pub name: String,
+  #[serde(rename = "type")]
+  pub kind: RegisterType,
+  #[serde(default)]
+  pub word_order: WordOrder,
+  #[serde(default = "default_scale")]
+  pub scale: f64,
+  #[serde(default)]
+  pub offset: f64,
+  #[serde(default)]
+  pub unit: Option<String>,
+}
+
+fn default_scale() -> f64 {
+  1.0
+}
+
+/// Register address → definition. Addresses at or above 40001 use the
+/// holding-register reference notation; smaller ones are zero-based offsets.
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct RegisterMap {
+  pub registers: BTreeMap<u32, RegisterDef>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappedValue {
+  pub value: f64,
+  pub unit: Option<String>,
+}
+
+/// Maps loaded with `modbus_load_register_map`, keyed by name.
+#[derive(Default)]
+pub struct RegisterMaps {
+  maps: Mutex<HashMap<String, RegisterMap>>,
+}
+
+fn register_offset(address: u32) -> Result<u16, SerialError> {
+  let offset = if address >= HOLDING_REFERENCE_BASE {
+    address - HOLDING_REFERENCE_BASE
+  } else {
+    address
+  };
+  u16::try_from(offset)
+    .map_err(|_| SerialError::InvalidInput(format!("Register address out of range: {address}")))
+}
+
+fn decode(def: &RegisterDef, words: &[u16]) -> f64 {
+  let (hi, lo) = match (def.kind.width(), def.word_order) {
+    (1, _) => (0, words[0]),
+    (_, WordOrder::Big) => (words[0], words[1]),
+    (_, WordOrder::Little) => (words[1], words[0]),
+  };
+  let bits = (u32::from(hi) << 16) | u32::from(lo);
+  let raw = match def.kind {
+    RegisterType::U16 => f64::from(lo),
+    RegisterType::I16 => f64::from(lo as i16),
+    RegisterType::U32 => f64::from(bits),
+    RegisterType::I32 => f64::from(bits as i32),
+    RegisterType::F32 => f64::from(f32::from_bits(bits)),
+  };
+  raw * def.scale + def.offset
+}
+
+#[tauri::command]
+pub fn modbus_load_register_map(
+  maps: State<RegisterMaps>,
+  name: String,
+  map: RegisterMap,
+) -> Result<usize, SerialError> {
+  if map.registers.is_empty() {
+    return Err(SerialError::InvalidInput("Register map is empty".to_string()));
+  }
+  for &address in map.registers.keys() {
+    register_offset(address)?;
+  }
+  let count = map.registers.len();
+  maps.maps.lock()?.insert(name.clone(), map);
+  eprintln!("[modbus] register map loaded name={name} registers={count}");
+  Ok(count)
+}
+
+/// Reads every register covered by `map_name` (in chunks of up to 125) and
+/// returns `{ name: { value, unit } }` with scale and offset applied.
+#[tauri::command]
+pub fn modbus_read_mapped(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  maps: State<RegisterMaps>,
+  slave: u8,
+  map_name: String,
+  timeout_ms: Option<u64>,
+  modbus_mode: Option<ModbusMode>,
+) -> Result<BTreeMap<String, MappedValue>, ModbusError> {
+  let map = maps
+    .maps
+    .lock()?
+    .get(&map_name)
+    .cloned()
+    .ok_or_else(|| SerialError::InvalidInput(format!("Unknown register map: {map_name}")))?;
+  let timeout_ms = timeout_ms.unwrap_or(500);
+  let mode = modbus_mode.unwrap_or_default();
+
+  let mut defs = Vec::with_capacity(map.registers.len());
+  for (&address, def) in &map.registers {
+    defs.push((register_offset(address)?, def));
+  }
+  let first = defs.iter().map(|(offset, _)| *offset).min().unwrap_or(0);
+  let end = defs
+    .iter()
+    .map(|(offset, def)| u32::from(*offset) + u32::from(def.kind.width()))
+    .max()
+    .unwrap_or(0);
+
+  let state = state.entry(port_id.as_deref())?;
+  let mut words = Vec::with_capacity((end - u32::from(first)) as usize);
+  let mut next = u32::from(first);
+  while next < end {
+    let count = (end - next).min(u32::from(MAX_CHUNK_REGISTERS)) as u16;
+    words.extend(read_holding_registers(&state, slave, next as u16, count, timeout_ms, mode)?);
+    next += u32::from(count);
+  }
+
+  let values = defs
+    .into_iter()
+    .map(|(offset, def)| {
+      let at = usize::from(offset - first);
+      let value = decode(def, &words[at..at + usize::from(def.kind.width())]);
+      (
+        def.name.clone(),
+        MappedValue {
+          value,
+          unit: def.unit.clone(),
+        },
+      )
+    })
+    .collect();
+  eprintln!("[modbus] read mapped ok slave={slave} map={map_name}");
+  Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn def(kind: RegisterType, word_order: WordOrder, scale: f64) -> RegisterDef {
+    RegisterDef {
+      name: "value".to_string(),
+      kind,
+      word_order,
+      scale,
+      offset: 0.0,
+      unit: None,
+    }
+  }
+
+  #[test]
+  fn decodes_scaled_and_32_bit_values() {
+    let volts = decode(&def(RegisterType::U16, WordOrder::Big, 0.1), &[2301]);
+    assert!((volts - 230.1).abs() < 1e-9);
+    assert_eq!(decode(&def(RegisterType::I16, WordOrder::Big, 1.0), &[0xFFFE]), -2.0);
+
+    let bits = 1.5f32.to_bits();
+    let (hi, lo) = ((bits >> 16) as u16, bits as u16);
+    assert_eq!(decode(&def(RegisterType::F32, WordOrder::Big, 1.0), &[hi, lo]), 1.5);
+    assert_eq!(decode(&def(RegisterType::F32, WordOrder::Little, 1.0), &[lo, hi]), 1.5);
+  }
+
+  #[test]
+  fn maps_reference_notation_to_offsets() {
+    assert_eq!(register_offset(40_001).unwrap(), 0);
+    assert_eq!(register_offset(40_011).unwrap(), 10);
+    assert_eq!(register_offset(7).unwrap(), 7);
+  }
+}
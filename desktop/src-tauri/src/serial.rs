@@ -2,25 +2,342 @@
 //! Provides Tauri commands, config/status types, and helpers for serial I/O.
 
 use std::{
+  collections::{BTreeSet, HashMap},
   fs,
-  io::{ErrorKind, Read, Write},
+  io::{self, ErrorKind, Read},
   path::Path,
-  sync::Mutex,
-  time::Duration,
+  sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex, PoisonError,
+  },
+  thread,
+  time::{Duration, Instant},
 };
 
-use tauri::State;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use crate::serial_io::SerialIo;
+use crate::serial_record::SerialRecorder;
+use crate::telemetry_bridge::TelemetryBridge;
 
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
 #[cfg(windows)]
 use std::os::windows::io::AsRawHandle;
 
+/// Error returned by every serial command. Serializes as `{ kind, message }`
+/// so the frontend can branch on `kind` while toasts keep showing `message`.
+#[derive(Clone, Debug)]
+pub enum SerialError {
+  NotOpen,
+  Busy(String),
+  Timeout,
+  PermissionDenied(String),
+  InvalidConfig(String),
+  InvalidInput(String),
+  Io(String),
+}
+
+impl SerialError {
+  pub(crate) fn kind(&self) -> &'static str {
+    match self {
+      SerialError::NotOpen => "NotOpen",
+      SerialError::Busy(_) => "Busy",
+      SerialError::Timeout => "Timeout",
+      SerialError::PermissionDenied(_) => "PermissionDenied",
+      SerialError::InvalidConfig(_) => "InvalidConfig",
+      SerialError::InvalidInput(_) => "InvalidInput",
+      SerialError::Io(_) => "Io",
+    }
+  }
+}
+
+impl std::fmt::Display for SerialError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SerialError::NotOpen => write!(f, "Serial port not open"),
+      SerialError::Timeout => write!(f, "Serial operation timed out"),
+      SerialError::Busy(msg)
+      | SerialError::PermissionDenied(msg)
+      | SerialError::InvalidConfig(msg)
+      | SerialError::InvalidInput(msg)
+      | SerialError::Io(msg) => write!(f, "{msg}"),
+    }
+  }
+}
+
+impl std::error::Error for SerialError {}
+
+impl serde::Serialize for SerialError {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+    let mut out = serializer.serialize_struct("SerialError", 2)?;
+    out.serialize_field("kind", self.kind())?;
+    out.serialize_field("message", &self.to_string())?;
+    out.end()
+  }
+}
+
+impl From<io::Error> for SerialError {
+  fn from(err: io::Error) -> Self {
+    match err.kind() {
+      ErrorKind::TimedOut => SerialError::Timeout,
+      ErrorKind::PermissionDenied => SerialError::PermissionDenied(err.to_string()),
+      _ => SerialError::Io(err.to_string()),
+    }
+  }
+}
+
+impl From<serialport::Error> for SerialError {
+  fn from(err: serialport::Error) -> Self {
+    match err.kind() {
+      serialport::ErrorKind::Io(ErrorKind::TimedOut) => SerialError::Timeout,
+      serialport::ErrorKind::Io(ErrorKind::PermissionDenied) => {
+        SerialError::PermissionDenied(err.to_string())
+      }
+      serialport::ErrorKind::InvalidInput => SerialError::InvalidConfig(err.to_string()),
+      _ => SerialError::Io(err.to_string()),
+    }
+  }
+}
+
+impl<T> From<PoisonError<T>> for SerialError {
+  fn from(_: PoisonError<T>) -> Self {
+    SerialError::Busy("Serial state mutex poisoned".to_string())
+  }
+}
+
+/// How long the stream loop sleeps when no bytes are pending.
+const STREAM_IDLE_MS: u64 = 5;
+/// Longest break `send_serial_break` will hold the line for.
+const MAX_BREAK_MS: u64 = 5000;
+/// Longest low pulse `pulse_serial_dtr` accepts; the command blocks for it.
+const MAX_DTR_PULSE_MS: u64 = 10_000;
+/// Longest gap `write_serial_data` puts between bytes.
+const MAX_INTER_BYTE_DELAY_US: u64 = 10_000;
+/// Longest paced write; pacing holds the port lock for the whole write.
+const MAX_PACED_WRITE_MS: u64 = 10_000;
+/// Rates tried by `detect_serial_baud` when no candidates are given.
+const DEFAULT_BAUD_CANDIDATES: &[u32] = &[9600, 19200, 38400, 57600, 115200, 4800, 2400, 1200];
+/// Baud deviation (percent) beyond which `serial:baud_mismatch` is emitted.
+/// Most UARTs resynchronise fine within ~2%.
+const BAUD_TOLERANCE_PERCENT: u64 = 2;
+/// A port must stay added/removed this long before the watcher reports it,
+/// so USB re-enumeration doesn't produce add/remove/add bursts.
+const PORT_WATCH_SETTLE_MS: u64 = 1000;
+
+/// Open ports keyed by the caller's `port_id`, plus the hot-plug watcher.
+/// The map lock is only held to look entries up; I/O locks the entry.
+#[derive(Default)]
 pub struct SerialState {
-  pub port: Mutex<Option<Box<dyn serialport::SerialPort>>>,
+  pub ports: Mutex<HashMap<String, Arc<PortEntry>>>,
+  pub port_watch: Mutex<Option<SerialStream>>,
+}
+
+/// One open port and everything running against it.
+#[derive(Default)]
+pub struct PortEntry {
+  pub id: String,
+  /// Alias the port was opened by; re-resolved on reconnect so a USB adapter
+  /// that comes back under a new path is still found.
+  pub alias: Option<String>,
+  pub port: Mutex<Option<Box<dyn SerialIo>>>,
+  pub stream: Mutex<Option<SerialStream>>,
+  pub modbus_poll: Mutex<Option<SerialStream>>,
+  pub driver_enable: Mutex<Option<DriverEnable>>,
+  /// Config of the currently (or most recently) open port, kept for auto-reconnect.
+  pub last_config: Mutex<Option<SerialConfig>>,
+  pub reconnecting: AtomicBool,
+  pub stats: SerialCounters,
+  /// Monotonic reference captured at open; stream chunks are timed against it.
+  pub opened_instant: Mutex<Option<Instant>>,
+  /// Active capture started by `start_serial_record`.
+  pub recorder: Mutex<Option<SerialRecorder>>,
+  /// Frame-to-telemetry routing set up by `serial_to_telemetry`.
+  pub telemetry_bridge: Mutex<Option<TelemetryBridge>>,
+  /// Requested timeouts; serialport has one timeout per handle, so each
+  /// command applies the matching value before it touches the port.
+  pub read_timeout_ms: AtomicU64,
+  pub write_timeout_ms: AtomicU64,
+}
+
+impl SerialState {
+  /// The entry for `port_id`. Without an id this is the only open port, so
+  /// single-port callers don't have to track ids.
+  pub fn entry(&self, port_id: Option<&str>) -> Result<Arc<PortEntry>, SerialError> {
+    let ports = self.ports.lock()?;
+    match port_id {
+      Some(id) => ports.get(id).cloned().ok_or(SerialError::NotOpen),
+      None if ports.len() > 1 => Err(SerialError::InvalidInput(
+        "Several ports are open; pass port_id to pick one".to_string(),
+      )),
+      None => ports.values().next().cloned().ok_or(SerialError::NotOpen),
+    }
+  }
+
+  fn entries(&self) -> Vec<Arc<PortEntry>> {
+    let mut entries: Vec<_> = self
+      .ports
+      .lock()
+      .map(|ports| ports.values().cloned().collect())
+      .unwrap_or_default();
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    entries
+  }
+
+  /// Configs of the ports currently open, ordered by id.
+  pub fn open_configs(&self) -> Vec<SerialConfig> {
+    self.entries().iter().filter_map(|entry| entry.open_config()).collect()
+  }
+
+  /// Whether any port is open or has a stream or capture running; quitting
+  /// would cut these off.
+  pub fn session_active(&self) -> bool {
+    self.entries().iter().any(|entry| entry.session_active())
+  }
+}
+
+impl PortEntry {
+  /// Config of the open port; `None` while waiting to reconnect.
+  pub fn open_config(&self) -> Option<SerialConfig> {
+    if self.port.lock().ok()?.is_none() {
+      return None;
+    }
+    self.last_config.lock().ok()?.clone()
+  }
+
+  fn session_active(&self) -> bool {
+    self.port.lock().is_ok_and(|port| port.is_some())
+      || self.stream.lock().is_ok_and(|stream| stream.is_some())
+      || self.recorder.lock().is_ok_and(|recorder| recorder.is_some())
+  }
+
+  /// Stops the workers, finishes any recording and drops the port. Clearing
+  /// `last_config` also tells a pending reconnect loop to give up.
+  fn shutdown(&self) -> Result<(), SerialError> {
+    stop_stream(self)?;
+    crate::modbus_poll::stop_poll(self)?;
+    if let Some(recorder) = self.recorder.lock()?.take() {
+      if let Err(err) = recorder.finish() {
+        eprintln!("[serial] recording finish failed port_id={}: {err}", self.id);
+      }
+    }
+    // Dropping the port clears TIOCEXCL and closes its fd, releasing the flock.
+    *self.port.lock()? = None;
+    *self.driver_enable.lock()? = None;
+    *self.last_config.lock()? = None;
+    self.stats.set_opened(false);
+    Ok(())
+  }
+}
+
+/// Brings the menus and tray status in line after the port opens or closes.
+fn port_state_changed<R: Runtime>(app: &AppHandle<R>) {
+  crate::menu::refresh_menus(app);
+  crate::menu::refresh_tray_status(app);
+}
+
+/// Session I/O counters, updated lock-free from commands and the stream loop.
+#[derive(Default)]
+pub struct SerialCounters {
+  bytes_read: AtomicU64,
+  bytes_written: AtomicU64,
+  read_errors: AtomicU64,
+  timeouts: AtomicU64,
+  /// Unix epoch millis of the last open; 0 while closed.
+  opened_at: AtomicU64,
+}
+
+impl SerialCounters {
+  fn record_read(&self, n: usize) {
+    self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+  }
+
+  fn record_write(&self, n: usize) {
+    self.bytes_written.fetch_add(n as u64, Ordering::Relaxed);
+  }
+
+  fn record_read_error(&self, err: &io::Error) {
+    if err.kind() == ErrorKind::TimedOut {
+      self.record_timeout();
+    } else {
+      self.read_errors.fetch_add(1, Ordering::Relaxed);
+    }
+  }
+
+  fn record_timeout(&self) {
+    self.timeouts.fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn reset(&self) {
+    self.bytes_read.store(0, Ordering::Relaxed);
+    self.bytes_written.store(0, Ordering::Relaxed);
+    self.read_errors.store(0, Ordering::Relaxed);
+    self.timeouts.store(0, Ordering::Relaxed);
+  }
+
+  fn set_opened(&self, open: bool) {
+    let now = if open {
+      chrono::Utc::now().timestamp_millis().max(1) as u64
+    } else {
+      0
+    };
+    self.opened_at.store(now, Ordering::Relaxed);
+  }
+
+  fn snapshot(&self) -> SerialStats {
+    let opened_at = self.opened_at.load(Ordering::Relaxed);
+    let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    SerialStats {
+      bytes_read: self.bytes_read.load(Ordering::Relaxed),
+      bytes_written: self.bytes_written.load(Ordering::Relaxed),
+      read_errors: self.read_errors.load(Ordering::Relaxed),
+      timeouts: self.timeouts.load(Ordering::Relaxed),
+      opened_at: (opened_at > 0).then_some(opened_at),
+      uptime_ms: if opened_at > 0 { now.saturating_sub(opened_at) } else { 0 },
+    }
+  }
+}
+
+/// How the RS-485 transceiver's driver-enable (RTS) line is handled around writes.
+#[derive(Clone, Copy, Debug)]
+pub enum DriverEnable {
+  /// The kernel driver toggles RTS itself (Linux `TIOCSRS485`).
+  Native,
+  /// RTS is raised before and lowered after each write by `write_serial_data`
+  /// and Modbus requests.
+  Manual { active_high: bool, post_tx_delay_us: u64 },
+}
+
+/// Handle to a background worker: the read loop started by
+/// `start_serial_stream`, the hot-plug poller from `start_port_watch`, or the
+/// Modbus poll scheduler.
+pub struct SerialStream {
+  stop: Arc<AtomicBool>,
+  handle: thread::JoinHandle<()>,
+}
+
+impl SerialStream {
+  pub(crate) fn spawn(worker: impl FnOnce(Arc<AtomicBool>) + Send + 'static) -> Self {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let handle = thread::spawn(move || worker(thread_stop));
+    Self { stop, handle }
+  }
+
+  pub(crate) fn is_running(&self) -> bool {
+    !self.handle.is_finished()
+  }
+
+  /// Signals the worker and waits for it to exit.
+  pub(crate) fn stop(self) {
+    self.stop.store(true, Ordering::Relaxed);
+    let _ = self.handle.join();
+  }
 }
 
-#[derive(serde::Deserialize)]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SerialConfig {
   pub port: String,
@@ -30,19 +347,104 @@ pub struct SerialConfig {
   pub data_bits: u8,
   pub read_timeout_ms: u64,
   pub write_timeout_ms: u64,
+  #[serde(default)]
+  pub rs485_de: bool,
+  #[serde(default)]
+  pub de_polarity: Option<String>,
+  #[serde(default)]
+  pub post_tx_delay_us: u64,
+  /// Keep other processes from opening the port (Unix TIOCEXCL plus flock);
+  /// `false` takes a shared flock instead. Windows ports are always exclusive.
+  #[serde(default = "default_exclusive")]
+  pub exclusive: bool,
+  #[serde(default)]
+  pub reconnect: bool,
+  #[serde(default = "default_reconnect_interval_ms")]
+  pub reconnect_interval_ms: u64,
+  #[serde(default)]
+  pub reconnect_max_attempts: Option<u32>,
+}
+
+fn default_reconnect_interval_ms() -> u64 {
+  1000
+}
+
+fn default_exclusive() -> bool {
+  true
 }
 
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SerialStatus {
+  /// Id to pass to the other commands; defaults to the port path.
+  pub port_id: String,
+  /// Concrete path, also when the port was opened by alias.
   pub port: String,
+  pub alias: Option<String>,
   pub baud: u32,
+  /// Rate the driver reports after opening; `None` if it couldn't be read back.
+  pub actual_baud: Option<u32>,
   pub parity: String,
   pub stop_bits: String,
   pub data_bits: u8,
-  pub timeout_ms: u64,
+  pub read_timeout_ms: u64,
+  pub write_timeout_ms: u64,
   pub fd: Option<i64>,
   pub handle: Option<i64>,
+  pub rs485_mode: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortInfo {
+  pub name: String,
+  /// `"USB"`, `"PCI"`, `"Bluetooth"`, or `"Unknown"`.
+  #[serde(rename = "type")]
+  pub port_type: String,
+  pub vid: Option<u16>,
+  pub pid: Option<u16>,
+  pub manufacturer: Option<String>,
+  pub product: Option<String>,
+  pub serial_number: Option<String>,
+}
+
+impl PortInfo {
+  fn unknown(name: String) -> Self {
+    Self {
+      name,
+      port_type: "Unknown".to_string(),
+      vid: None,
+      pid: None,
+      manufacturer: None,
+      product: None,
+      serial_number: None,
+    }
+  }
+}
+
+impl From<serialport::SerialPortInfo> for PortInfo {
+  fn from(info: serialport::SerialPortInfo) -> Self {
+    match info.port_type {
+      serialport::SerialPortType::UsbPort(usb) => Self {
+        name: info.port_name,
+        port_type: "USB".to_string(),
+        vid: Some(usb.vid),
+        pid: Some(usb.pid),
+        manufacturer: usb.manufacturer,
+        product: usb.product,
+        serial_number: usb.serial_number,
+      },
+      serialport::SerialPortType::PciPort => Self {
+        port_type: "PCI".to_string(),
+        ..Self::unknown(info.port_name)
+      },
+      serialport::SerialPortType::BluetoothPort => Self {
+        port_type: "Bluetooth".to_string(),
+        ..Self::unknown(info.port_name)
+      },
+      serialport::SerialPortType::Unknown => Self::unknown(info.port_name),
+    }
+  }
 }
 
 #[derive(serde::Serialize)]
@@ -51,52 +453,258 @@ pub struct SerialRead {
   pub len: usize,
   pub text: String,
   pub hex: String,
+  pub base64: String,
+}
+
+impl SerialRead {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    Self {
+      len: bytes.len(),
+      text: String::from_utf8_lossy(bytes).to_string(),
+      hex: bytes_to_hex(bytes),
+      base64: BASE64.encode(bytes),
+    }
+  }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialReadUntil {
+  #[serde(flatten)]
+  pub read: SerialRead,
+  pub terminated: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialLines {
+  pub cts: bool,
+  pub dsr: bool,
+  pub carrier_detect: bool,
+  pub ring: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialStats {
+  pub bytes_read: u64,
+  pub bytes_written: u64,
+  pub read_errors: u64,
+  pub timeouts: u64,
+  pub opened_at: Option<u64>,
+  pub uptime_ms: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialAvail {
+  pub to_read: u32,
+  pub to_write: u32,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialBaudMismatch {
+  pub port_id: String,
+  pub port: String,
+  pub requested: u32,
+  pub actual: u32,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialReconnect {
+  pub port_id: String,
+  pub port: String,
+  pub attempt: u32,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialChunk {
+  /// Port the bytes came from; absent for replayed recordings.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub port_id: Option<String>,
+  pub len: usize,
+  pub text: String,
+  pub hex: String,
+  pub ts: String,
+  /// Per-stream counter; a gap means events were dropped.
+  pub seq: u64,
+  /// Microseconds since the port was opened (monotonic clock).
+  pub mono_us: u128,
 }
 
-fn parse_parity(parity: &str) -> Result<serialport::Parity, String> {
+fn parse_parity(parity: &str) -> Result<serialport::Parity, SerialError> {
   match parity {
     "None" => Ok(serialport::Parity::None),
     "Even" => Ok(serialport::Parity::Even),
     "Odd" => Ok(serialport::Parity::Odd),
-    _ => Err(format!("Unsupported parity: {parity}")),
+    _ => Err(SerialError::InvalidConfig(format!("Unsupported parity: {parity}"))),
+  }
+}
+
+/// serialport's `StopBits` has no 1.5, so it is kept here until `open_port`
+/// maps it to a platform setting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StopBits {
+  One,
+  OnePointFive,
+  Two,
+}
+
+fn parse_stop_bits(stop_bits: &str) -> Result<StopBits, SerialError> {
+  match stop_bits {
+    "1" => Ok(StopBits::One),
+    "1.5" => Ok(StopBits::OnePointFive),
+    "2" => Ok(StopBits::Two),
+    _ => Err(SerialError::InvalidConfig(format!("Unsupported stop bits: {stop_bits}"))),
   }
 }
 
-fn parse_stop_bits(stop_bits: &str) -> Result<serialport::StopBits, String> {
+/// termios has no 1.5 flag: with 5 data bits, CSTOPB (serialport's `Two`)
+/// makes the UART send 1.5 stop bits instead, which is how POSIX spells it.
+/// serialport can't set the Windows DCB `ONE5STOPBITS`, so it's an error there.
+fn stop_bits_setting(
+  stop_bits: StopBits,
+  data_bits: u8,
+) -> Result<serialport::StopBits, SerialError> {
   match stop_bits {
-    "1" => Ok(serialport::StopBits::One),
-    "2" => Ok(serialport::StopBits::Two),
-    _ => Err(format!("Unsupported stop bits: {stop_bits}")),
+    StopBits::One => Ok(serialport::StopBits::One),
+    StopBits::Two => Ok(serialport::StopBits::Two),
+    StopBits::OnePointFive if data_bits != 5 => Err(SerialError::InvalidConfig(format!(
+      "1.5 stop bits require 5 data bits (got {data_bits})"
+    ))),
+    #[cfg(unix)]
+    StopBits::OnePointFive => Ok(serialport::StopBits::Two),
+    #[cfg(not(unix))]
+    StopBits::OnePointFive => Err(SerialError::InvalidConfig(
+      "1.5 stop bits are not supported on this platform".to_string(),
+    )),
   }
 }
 
-fn parse_data_bits(data_bits: u8) -> Result<serialport::DataBits, String> {
+fn parse_data_bits(data_bits: u8) -> Result<serialport::DataBits, SerialError> {
   match data_bits {
     5 => Ok(serialport::DataBits::Five),
     6 => Ok(serialport::DataBits::Six),
     7 => Ok(serialport::DataBits::Seven),
     8 => Ok(serialport::DataBits::Eight),
-    _ => Err(format!("Unsupported data bits: {data_bits}")),
+    _ => Err(SerialError::InvalidConfig(format!("Unsupported data bits: {data_bits}"))),
+  }
+}
+
+/// Terminator appended to text writes; hex payloads are sent verbatim.
+fn parse_line_ending(line_ending: Option<&str>) -> Result<&'static [u8], SerialError> {
+  match line_ending {
+    None | Some("none") => Ok(b""),
+    Some("cr") => Ok(b"\r"),
+    Some("lf") => Ok(b"\n"),
+    Some("crlf") => Ok(b"\r\n"),
+    Some(other) => Err(SerialError::InvalidInput(format!("Unsupported line ending: {other}"))),
+  }
+}
+
+/// Returns `true` when the driver is enabled by driving RTS high.
+fn parse_de_polarity(polarity: Option<&str>) -> Result<bool, SerialError> {
+  match polarity {
+    None | Some("high") => Ok(true),
+    Some("low") => Ok(false),
+    Some(other) => Err(SerialError::InvalidConfig(format!("Unsupported DE polarity: {other}"))),
+  }
+}
+
+impl SerialConfig {
+  /// Runs the setting checks `open_port` does without touching a port, so a
+  /// stored config is rejected when it's saved rather than when it's opened.
+  pub fn validate(&self) -> Result<(), SerialError> {
+    parse_parity(&self.parity)?;
+    stop_bits_setting(parse_stop_bits(&self.stop_bits)?, self.data_bits)?;
+    parse_data_bits(self.data_bits)?;
+    parse_de_polarity(self.de_polarity.as_deref())?;
+    Ok(())
+  }
+}
+
+/// Mirrors the kernel's `struct serial_rs485` (32 bytes); libc does not expose it.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct SerialRs485 {
+  flags: u32,
+  delay_rts_before_send: u32,
+  delay_rts_after_send: u32,
+  padding: [u32; 5],
+}
+
+/// Asks the UART driver to toggle RTS itself. Most USB adapters reject this
+/// with `ENOTTY`, in which case the caller falls back to manual RTS control.
+#[cfg(target_os = "linux")]
+fn enable_native_rs485(fd: i32, active_high: bool, post_tx_delay_us: u64) -> io::Result<()> {
+  const SER_RS485_ENABLED: u32 = 1 << 0;
+  const SER_RS485_RTS_ON_SEND: u32 = 1 << 1;
+  const SER_RS485_RTS_AFTER_SEND: u32 = 1 << 2;
+
+  let mut conf = SerialRs485 {
+    flags: SER_RS485_ENABLED,
+    delay_rts_after_send: post_tx_delay_us.div_ceil(1000).min(u32::MAX as u64) as u32,
+    ..Default::default()
+  };
+  conf.flags |= if active_high {
+    SER_RS485_RTS_ON_SEND
+  } else {
+    SER_RS485_RTS_AFTER_SEND
+  };
+
+  // SAFETY: `fd` is an open tty owned by the caller and `conf` matches the kernel layout.
+  let rc = unsafe { libc::ioctl(fd, libc::TIOCSRS485 as _, &conf as *const SerialRs485) };
+  if rc < 0 {
+    return Err(io::Error::last_os_error());
   }
+  Ok(())
 }
 
-fn hex_to_bytes(input: &str) -> Result<Vec<u8>, String> {
+pub(crate) fn hex_to_bytes(input: &str) -> Result<Vec<u8>, SerialError> {
   let filtered: String = input.chars().filter(|c| !c.is_whitespace()).collect();
   if filtered.len() % 2 != 0 {
-    return Err("Hex input must have an even number of digits".to_string());
+    return Err(SerialError::InvalidInput(
+      "Hex input must have an even number of digits".to_string(),
+    ));
   }
 
   let mut bytes = Vec::with_capacity(filtered.len() / 2);
   let chars: Vec<char> = filtered.chars().collect();
   for i in (0..chars.len()).step_by(2) {
-    let hi = chars[i].to_digit(16).ok_or_else(|| "Invalid hex digit".to_string())?;
-    let lo = chars[i + 1].to_digit(16).ok_or_else(|| "Invalid hex digit".to_string())?;
+    let hi = chars[i]
+      .to_digit(16)
+      .ok_or_else(|| SerialError::InvalidInput("Invalid hex digit".to_string()))?;
+    let lo = chars[i + 1]
+      .to_digit(16)
+      .ok_or_else(|| SerialError::InvalidInput("Invalid hex digit".to_string()))?;
     bytes.push(((hi << 4) | lo) as u8);
   }
   Ok(bytes)
 }
 
-fn bytes_to_hex(bytes: &[u8]) -> String {
+pub(crate) fn base64_to_bytes(input: &str) -> Result<Vec<u8>, SerialError> {
+  let filtered: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+  BASE64.decode(filtered).map_err(|err| {
+    let message = match err {
+      base64::DecodeError::InvalidPadding | base64::DecodeError::InvalidLength(_) => {
+        "Base64 input has invalid padding".to_string()
+      }
+      base64::DecodeError::InvalidByte(offset, _) => {
+        format!("Invalid base64 character at position {offset}")
+      }
+      base64::DecodeError::InvalidLastSymbol(offset, _) => {
+        format!("Invalid base64 final symbol at position {offset}")
+      }
+    };
+    SerialError::InvalidInput(message)
+  })
+}
+
+pub(crate) fn bytes_to_hex(bytes: &[u8]) -> String {
   bytes
     .iter()
     .map(|b| format!("{:02X}", b))
@@ -104,174 +712,1449 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
     .join(" ")
 }
 
-#[tauri::command]
-pub fn list_serial_ports() -> Vec<String> {
-  let mut ports: Vec<String> = serialport::available_ports()
-    .map(|ports| ports.into_iter().map(|port| port.port_name).collect())
-    .unwrap_or_default();
+fn stop_stream(state: &PortEntry) -> Result<(), SerialError> {
+  let stream = state.stream.lock()?.take();
+  if let Some(stream) = stream {
+    stream.stop();
+    eprintln!("[serial] stream stopped port_id={}", state.id);
+  }
+  Ok(())
+}
 
-  if let Ok(entries) = fs::read_dir("/dev") {
-    for entry in entries.flatten() {
-      if let Ok(name) = entry.file_name().into_string() {
-        if name.starts_with("ttyUSB") || name.starts_with("ttyACM") {
-          ports.push(format!("/dev/{name}"));
+pub(crate) fn stream_active(state: &PortEntry) -> Result<bool, SerialError> {
+  let guard = state.stream.lock()?;
+  Ok(guard.as_ref().is_some_and(SerialStream::is_running))
+}
+
+/// Background read loop: only reads what is already buffered so the port
+/// mutex is never held across a blocking read and writes can interleave.
+fn run_stream<R: Runtime>(
+  app: AppHandle<R>,
+  state: Arc<PortEntry>,
+  stop: Arc<AtomicBool>,
+  chunk_size: usize,
+) {
+  let epoch = state
+    .opened_instant
+    .lock()
+    .ok()
+    .and_then(|opened| *opened)
+    .unwrap_or_else(Instant::now);
+  let mut seq: u64 = 0;
+  let mut buf = vec![0u8; chunk_size];
+  while !stop.load(Ordering::Relaxed) {
+    let result = {
+      let mut guard = match state.port.lock() {
+        Ok(guard) => guard,
+        Err(err) => {
+          let _ = app.emit("serial:error", SerialError::from(err));
+          break;
+        }
+      };
+      let Some(port) = guard.as_mut() else {
+        if state.reconnecting.load(Ordering::SeqCst) {
+          drop(guard);
+          thread::sleep(Duration::from_millis(STREAM_IDLE_MS * 20));
+          continue;
         }
+        let _ = app.emit("serial:error", SerialError::NotOpen);
+        break;
+      };
+      match port.bytes_to_read() {
+        Ok(0) => Ok(0),
+        Ok(pending) => {
+          let want = (pending as usize).min(buf.len());
+          use_timeout(port.as_mut(), &state.read_timeout_ms)
+            .map_err(io::Error::from)
+            .and_then(|_| port.read(&mut buf[..want]))
+        }
+        Err(err) => Err(io::Error::from(err)),
       }
-    }
-  }
+    };
 
-  if let Ok(entries) = fs::read_dir("/dev/serial/by-id") {
-    for entry in entries.flatten() {
-      let path = entry.path();
-      if let Ok(target) = fs::read_link(&path) {
-        let resolved = if target.is_absolute() {
-          target
-        } else {
-          path
-            .parent()
-            .unwrap_or_else(|| Path::new("/dev"))
-            .join(target)
+    match result {
+      Ok(0) => thread::sleep(Duration::from_millis(STREAM_IDLE_MS)),
+      Ok(n) => {
+        state.stats.record_read(n);
+        let bytes = &buf[..n];
+        let chunk = SerialChunk {
+          port_id: Some(state.id.clone()),
+          len: n,
+          text: String::from_utf8_lossy(bytes).to_string(),
+          hex: bytes_to_hex(bytes),
+          ts: chrono::Utc::now().to_rfc3339(),
+          seq,
+          mono_us: epoch.elapsed().as_micros(),
         };
-        if let Ok(canon) = resolved.canonicalize() {
-          ports.push(canon.display().to_string());
-        } else {
-          ports.push(resolved.display().to_string());
+        seq += 1;
+        let _ = app.emit("serial:data", chunk);
+        if let Ok(mut recorder) = state.recorder.lock() {
+          if let Some(Err(err)) = recorder.as_mut().map(|recorder| recorder.record(bytes)) {
+            eprintln!("[serial] recording failed: {err}");
+            let _ = app.emit("serial:error", SerialError::from(err));
+            *recorder = None;
+          }
+        }
+        if let Ok(mut bridge) = state.telemetry_bridge.lock() {
+          if let Some(bridge) = bridge.as_mut() {
+            bridge.feed(&app, bytes);
+          }
+        }
+      }
+      Err(err) if err.kind() == ErrorKind::TimedOut => {
+        state.stats.record_timeout();
+        continue;
+      }
+      Err(err) => {
+        eprintln!("[serial] stream error: {err}");
+        state.stats.record_read_error(&err);
+        let disconnected = is_disconnect(&err, &state);
+        let _ = app.emit("serial:error", SerialError::from(err));
+        // Keep the stream alive across a reconnect; it resumes once the port is back.
+        if !(disconnected && schedule_reconnect(&app, &state)) {
+          break;
         }
       }
     }
   }
-
-  ports.sort();
-  ports.dedup();
-  ports
 }
 
-#[tauri::command]
-pub fn open_serial_port(
-  state: State<SerialState>,
-  config: SerialConfig,
-) -> Result<SerialStatus, String> {
-  if config.port.trim().is_empty() {
-    return Err("Port is required".to_string());
+/// Best-effort check for "the device went away" (USB unplug, re-enumeration).
+fn is_disconnect(err: &io::Error, state: &PortEntry) -> bool {
+  #[cfg(unix)]
+  const DISCONNECT_OS_ERRORS: &[i32] = &[5 /* EIO */, 6 /* ENXIO */, 19 /* ENODEV */];
+  #[cfg(windows)]
+  const DISCONNECT_OS_ERRORS: &[i32] = &[
+    22,   // ERROR_BAD_COMMAND
+    995,  // ERROR_OPERATION_ABORTED
+    1167, // ERROR_DEVICE_NOT_CONNECTED
+  ];
+  #[cfg(not(any(unix, windows)))]
+  const DISCONNECT_OS_ERRORS: &[i32] = &[];
+
+  if matches!(
+    err.kind(),
+    ErrorKind::BrokenPipe
+      | ErrorKind::NotConnected
+      | ErrorKind::NotFound
+      | ErrorKind::UnexpectedEof
+      | ErrorKind::ConnectionAborted
+      | ErrorKind::ConnectionReset
+  ) {
+    return true;
+  }
+  if err
+    .raw_os_error()
+    .is_some_and(|code| DISCONNECT_OS_ERRORS.contains(&code))
+  {
+    return true;
   }
+  // serialport flattens some errno values, so also check whether the device node vanished.
+  cfg!(unix)
+    && state
+      .last_config
+      .lock()
+      .ok()
+      .and_then(|config| config.as_ref().map(|config| !Path::new(&config.port).exists()))
+      .unwrap_or(false)
+}
 
-  eprintln!(
-    "[serial] open requested port={} baud={} parity={} stop_bits={} data_bits={} read_timeout_ms={} write_timeout_ms={}",
-    config.port,
-    config.baud,
-    config.parity,
-    config.stop_bits,
-    config.data_bits,
-    config.read_timeout_ms,
-    config.write_timeout_ms
-  );
+/// Converts an I/O failure into a `SerialError`, kicking off auto-reconnect
+/// first when it looks like the device was unplugged.
+fn io_failure<R: Runtime>(
+  app: &AppHandle<R>,
+  state: &Arc<PortEntry>,
+  err: io::Error,
+) -> SerialError {
+  if is_disconnect(&err, state) {
+    schedule_reconnect(app, state);
+  }
+  err.into()
+}
 
-  {
-    let mut guard = state.port.lock().map_err(|_| "Serial port mutex poisoned".to_string())?;
+/// Starts the reconnect loop if the last-opened config asked for it.
+/// Returns `true` when a reconnect is (or already was) in progress.
+fn schedule_reconnect<R: Runtime>(app: &AppHandle<R>, state: &Arc<PortEntry>) -> bool {
+  let config = match state.last_config.lock() {
+    Ok(config) => config.clone(),
+    Err(_) => return false,
+  };
+  let Some(config) = config.filter(|config| config.reconnect) else {
+    return false;
+  };
+  if state.reconnecting.swap(true, Ordering::SeqCst) {
+    return true;
+  }
+  if let Ok(mut guard) = state.port.lock() {
     *guard = None;
   }
+  port_state_changed(app);
+  eprintln!("[serial] disconnect detected port={}, reconnecting", config.port);
+  let app = app.clone();
+  let state = state.clone();
+  thread::spawn(move || run_reconnect(app, state, config));
+  true
+}
+
+fn run_reconnect<R: Runtime>(app: AppHandle<R>, state: Arc<PortEntry>, config: SerialConfig) {
+  let interval = Duration::from_millis(config.reconnect_interval_ms.max(100));
+  let mut attempt: u32 = 0;
+
+  loop {
+    // Give up quietly if the user closed or reopened a port in the meantime.
+    let superseded = state
+      .last_config
+      .lock()
+      .map(|last| last.as_ref().map(|last| last.port != config.port).unwrap_or(true))
+      .unwrap_or(true)
+      || state.port.lock().map(|port| port.is_some()).unwrap_or(true);
+    if superseded {
+      break;
+    }
+    if config.reconnect_max_attempts.is_some_and(|max| attempt >= max) {
+      eprintln!("[serial] reconnect gave up port={} attempts={attempt}", config.port);
+      let _ = app.emit(
+        "serial:reconnect_failed",
+        SerialReconnect {
+          port_id: state.id.clone(),
+          port: config.port.clone(),
+          attempt,
+        },
+      );
+      break;
+    }
+
+    attempt += 1;
+    thread::sleep(interval);
+    let _ = app.emit(
+      "serial:reconnecting",
+      SerialReconnect {
+        port_id: state.id.clone(),
+        port: config.port.clone(),
+        attempt,
+      },
+    );
+
+    // Follow the alias to wherever the device enumerates now; keep trying
+    // the old path while it can't be resolved.
+    let target = match &state.alias {
+      Some(alias) => match crate::port_alias::resolve_port_alias(&app, alias) {
+        Ok(Some(port)) => SerialConfig {
+          port,
+          ..config.clone()
+        },
+        _ => config.clone(),
+      },
+      None => config.clone(),
+    };
+    match open_port(&target) {
+      Ok(opened) => {
+        // Same lock order as write_serial_data: port first, then driver_enable.
+        if let Ok(mut port) = state.port.lock() {
+          if let Ok(mut driver_enable) = state.driver_enable.lock() {
+            *driver_enable = opened.driver_enable;
+          }
+          *port = Some(opened.port);
+        }
+        if target.port != config.port {
+          if let Ok(mut last) = state.last_config.lock() {
+            *last = Some(target.clone());
+          }
+        }
+        port_state_changed(&app);
+        eprintln!("[serial] reconnect ok port={} attempt={attempt}", target.port);
+        let _ = app.emit(
+          "serial:reconnected",
+          SerialReconnect {
+            port_id: state.id.clone(),
+            port: target.port.clone(),
+            attempt,
+          },
+        );
+        break;
+      }
+      Err(err) => eprintln!("[serial] reconnect attempt={attempt} failed: {err}"),
+    }
+  }
+  state.reconnecting.store(false, Ordering::SeqCst);
+}
+
+/// A freshly opened port plus the platform details reported in `SerialStatus`.
+struct OpenedPort {
+  port: Box<dyn SerialIo>,
+  fd: Option<i64>,
+  handle: Option<i64>,
+  driver_enable: Option<DriverEnable>,
+}
 
+/// Opens and configures the port described by `config`. Shared by
+/// `open_serial_port` and the auto-reconnect loop.
+fn open_port(config: &SerialConfig) -> Result<OpenedPort, SerialError> {
   let parity = parse_parity(&config.parity)?;
-  let stop_bits = parse_stop_bits(&config.stop_bits)?;
+  let stop_bits = stop_bits_setting(parse_stop_bits(&config.stop_bits)?, config.data_bits)?;
   let data_bits = parse_data_bits(config.data_bits)?;
-  let timeout_ms = config.read_timeout_ms.max(config.write_timeout_ms).max(100);
+  let de_active_high = parse_de_polarity(config.de_polarity.as_deref())?;
 
   let builder = serialport::new(config.port.clone(), config.baud)
     .parity(parity)
     .stop_bits(stop_bits)
     .data_bits(data_bits)
-    .timeout(Duration::from_millis(timeout_ms));
+    .timeout(Duration::from_millis(config.read_timeout_ms.max(1)));
+  // Exclusive opens take TIOCEXCL plus an exclusive flock; shared opens take
+  // only a shared flock, so they coexist with other shared openers but not
+  // with an exclusive one. TIOCEXCL doesn't stop root.
+  #[cfg(unix)]
+  let builder = builder.exclusive(config.exclusive);
 
   #[cfg(unix)]
-  let (port, fd, handle) = {
-    let port = serialport::TTYPort::open(&builder).map_err(|err| err.to_string())?;
+  let (mut port, fd, handle) = {
+    // A conflicting TIOCEXCL (EBUSY) or flock both surface as `NoDevice`.
+    let busy = |err: serialport::Error| match err.kind() {
+      serialport::ErrorKind::NoDevice => {
+        SerialError::Busy(format!("{} is in use by another process ({err})", config.port))
+      }
+      _ => err.into(),
+    };
+    let port = serialport::TTYPort::open(&builder).map_err(busy)?;
     let fd = port.as_raw_fd() as i64;
-    (Box::new(port) as Box<dyn serialport::SerialPort>, Some(fd), None)
+    (Box::new(port) as Box<dyn SerialIo>, Some(fd), None)
   };
 
   #[cfg(windows)]
-  let (port, fd, handle) = {
-    let port = serialport::COMPort::open(&builder).map_err(|err| err.to_string())?;
+  let (mut port, fd, handle) = {
+    let port = serialport::COMPort::open(&builder)?;
     let handle = port.as_raw_handle() as i64;
-    (Box::new(port) as Box<dyn serialport::SerialPort>, None, Some(handle))
+    (Box::new(port) as Box<dyn SerialIo>, None, Some(handle))
   };
 
+  // serialport has no backend for other targets; `builder.open()` would only fail.
   #[cfg(not(any(unix, windows)))]
-  let (port, fd, handle) = {
-    let port = builder.open().map_err(|err| err.to_string())?;
-    (port, None, None)
+  let (mut port, fd, handle): (Box<dyn SerialIo>, Option<i64>, Option<i64>) = {
+    let _ = builder;
+    return Err(SerialError::InvalidConfig(
+      "Serial ports are not supported on this platform".to_string(),
+    ));
   };
 
-  let mut guard = state.port.lock().map_err(|_| "Serial port mutex poisoned".to_string())?;
-  *guard = Some(port);
-  eprintln!(
-    "[serial] open ok port={} baud={} parity={} stop_bits={} data_bits={} timeout_ms={} fd={:?} handle={:?}",
-    config.port,
-    config.baud,
-    config.parity,
-    config.stop_bits,
+  let driver_enable = if config.rs485_de {
+    #[cfg(target_os = "linux")]
+    let native = fd
+      .map(|fd| enable_native_rs485(fd as i32, de_active_high, config.post_tx_delay_us))
+      .is_some_and(|result| match result {
+        Ok(()) => true,
+        Err(err) => {
+          eprintln!("[serial] native rs485 unavailable, using manual RTS: {err}");
+          false
+        }
+      });
+    #[cfg(not(target_os = "linux"))]
+    let native = false;
+
+    if native {
+      Some(DriverEnable::Native)
+    } else {
+      // Park the transceiver in receive mode until the first write.
+      port.write_request_to_send(!de_active_high)?;
+      Some(DriverEnable::Manual {
+        active_high: de_active_high,
+        post_tx_delay_us: config.post_tx_delay_us,
+      })
+    }
+  } else {
+    None
+  };
+
+  Ok(OpenedPort {
+    port,
+    fd,
+    handle,
+    driver_enable,
+  })
+}
+
+/// Sets the handle timeout to `timeout_ms` unless it is already applied.
+fn use_timeout(port: &mut dyn SerialIo, timeout_ms: &AtomicU64) -> serialport::Result<()> {
+  let timeout = Duration::from_millis(timeout_ms.load(Ordering::Relaxed).max(1));
+  if port.timeout() != timeout {
+    port.set_timeout(timeout)?;
+  }
+  Ok(())
+}
+
+/// USB/ACM device nodes (macOS `cu.*` call-out devices) and resolved
+/// `/dev/serial/by-id` links that `available_ports()` can miss (e.g. without udev).
+#[cfg(not(windows))]
+fn scan_dev_ports() -> Vec<String> {
+  let mut ports = Vec::new();
+
+  if let Ok(entries) = fs::read_dir("/dev") {
+    for entry in entries.flatten() {
+      if let Ok(name) = entry.file_name().into_string() {
+        if name.starts_with("ttyUSB") || name.starts_with("ttyACM") {
+          ports.push(format!("/dev/{name}"));
+        }
+        #[cfg(target_os = "macos")]
+        if name.starts_with("cu.") {
+          ports.push(format!("/dev/{name}"));
+        }
+      }
+    }
+  }
+
+  if let Ok(entries) = fs::read_dir("/dev/serial/by-id") {
+    for entry in entries.flatten() {
+      let path = entry.path();
+      if let Ok(target) = fs::read_link(&path) {
+        let resolved = if target.is_absolute() {
+          target
+        } else {
+          path
+            .parent()
+            .unwrap_or_else(|| Path::new("/dev"))
+            .join(target)
+        };
+        if let Ok(canon) = resolved.canonicalize() {
+          ports.push(canon.display().to_string());
+        } else {
+          ports.push(resolved.display().to_string());
+        }
+      }
+    }
+  }
+
+  ports
+}
+
+/// COM names registered under `HARDWARE\DEVICEMAP\SERIALCOMM`, which some
+/// driver stacks populate without `available_ports()` seeing them.
+#[cfg(windows)]
+fn scan_registry_ports() -> Vec<String> {
+  use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+  let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+  let Ok(key) = hklm.open_subkey(r"HARDWARE\DEVICEMAP\SERIALCOMM") else {
+    return Vec::new();
+  };
+  key
+    .enum_values()
+    .flatten()
+    .filter_map(|(name, _)| key.get_value::<String, _>(&name).ok())
+    .map(|port| port.trim_end_matches('\0').trim().to_string())
+    .filter(|port| !port.is_empty())
+    .collect()
+}
+
+/// Ports found outside `available_ports()`: the registry on Windows, the
+/// `/dev` scan elsewhere.
+#[cfg(windows)]
+fn scan_extra_ports() -> Vec<String> {
+  scan_registry_ports()
+}
+
+#[cfg(not(windows))]
+fn scan_extra_ports() -> Vec<String> {
+  scan_dev_ports()
+}
+
+/// True for a macOS `/dev/tty.X` dial-in node whose `/dev/cu.X` twin is also
+/// listed; opening the `tty.` side blocks until carrier detect is asserted.
+#[cfg(target_os = "macos")]
+fn is_shadowed_dialin(name: &str, names: &[&str]) -> bool {
+  name
+    .strip_prefix("/dev/tty.")
+    .is_some_and(|rest| names.contains(&format!("/dev/cu.{rest}").as_str()))
+}
+
+#[tauri::command]
+pub fn list_serial_ports() -> Vec<String> {
+  let mut ports: Vec<String> = serialport::available_ports()
+    .map(|ports| ports.into_iter().map(|port| port.port_name).collect())
+    .unwrap_or_default();
+  ports.extend(scan_extra_ports());
+
+  ports.sort();
+  ports.dedup();
+  #[cfg(target_os = "macos")]
+  {
+    let names: Vec<String> = ports.clone();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    ports.retain(|port| !is_shadowed_dialin(port, &names));
+  }
+  ports
+}
+
+#[tauri::command]
+pub fn list_serial_ports_detailed() -> Vec<PortInfo> {
+  let mut ports: Vec<PortInfo> = serialport::available_ports()
+    .map(|ports| ports.into_iter().map(PortInfo::from).collect())
+    .unwrap_or_default();
+
+  for name in scan_extra_ports() {
+    if !ports.iter().any(|port| port.name == name) {
+      ports.push(PortInfo::unknown(name));
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    let names: Vec<String> = ports.iter().map(|port| port.name.clone()).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    ports.retain(|port| !is_shadowed_dialin(&port.name, &names));
+  }
+
+  ports.sort_by(|a, b| a.name.cmp(&b.name));
+  ports
+}
+
+/// Adds an actionable hint to permission failures; the raw error stays appended.
+fn with_open_hint(err: SerialError, port: &str) -> SerialError {
+  let SerialError::PermissionDenied(raw) = err else {
+    return err;
+  };
+  #[cfg(target_os = "linux")]
+  let hint = format!(
+    "Permission denied opening {port}. Add your user to the dialout group with \
+     `sudo usermod -aG dialout $USER`, then log out and back in."
+  );
+  #[cfg(windows)]
+  let hint = format!("{port} is already in use by another application. Close it and retry.");
+  #[cfg(not(any(target_os = "linux", windows)))]
+  let hint = format!("Permission denied opening {port}.");
+  SerialError::PermissionDenied(format!("{hint} ({raw})"))
+}
+
+fn baud_within_tolerance(requested: u32, actual: u32) -> bool {
+  let diff = u64::from(requested.abs_diff(actual));
+  diff * 100 <= u64::from(requested) * BAUD_TOLERANCE_PERCENT
+}
+
+/// Opens `config.port` under `port_id` (default: the port path), replacing
+/// whatever was open under that id. Other ids are left alone, so several
+/// buses can be open at once. `config.port` may also name a `set_port_alias`
+/// alias, which is resolved to the device's current path.
+#[tauri::command]
+pub fn open_serial_port<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  mut config: SerialConfig,
+  port_id: Option<String>,
+) -> Result<SerialStatus, SerialError> {
+  if config.port.trim().is_empty() {
+    return Err(SerialError::InvalidConfig("Port is required".to_string()));
+  }
+  let port_id = match port_id.map(|id| id.trim().to_string()) {
+    Some(id) if !id.is_empty() => id,
+    _ => config.port.clone(),
+  };
+  let alias = match crate::port_alias::resolve_port_alias(&app, &config.port)? {
+    Some(path) => Some(std::mem::replace(&mut config.port, path)),
+    None => None,
+  };
+  let holder = state.entries().into_iter().find(|entry| {
+    entry.id != port_id
+      && entry.last_config.lock().is_ok_and(|last| {
+        last.as_ref().is_some_and(|last| last.port == config.port)
+      })
+  });
+  if let Some(holder) = holder {
+    return Err(SerialError::Busy(format!(
+      "{} is already open as port_id {}",
+      config.port, holder.id
+    )));
+  }
+
+  eprintln!(
+    "[serial] open requested port={} baud={} parity={} stop_bits={} data_bits={} read_timeout_ms={} write_timeout_ms={}",
+    config.port,
+    config.baud,
+    config.parity,
+    config.stop_bits,
     config.data_bits,
-    timeout_ms,
+    config.read_timeout_ms,
+    config.write_timeout_ms
+  );
+
+  let previous = state.ports.lock()?.remove(&port_id);
+  if let Some(previous) = previous {
+    previous.shutdown()?;
+  }
+
+  let OpenedPort {
+    port,
+    fd,
+    handle,
+    driver_enable,
+  } = match open_port(&config) {
+    Ok(opened) => opened,
+    Err(err) => {
+      // Any previously open port was already dropped above.
+      port_state_changed(&app);
+      return Err(with_open_hint(err, &config.port));
+    }
+  };
+  let actual_baud = match port.baud_rate() {
+    Ok(actual) => Some(actual),
+    Err(err) => {
+      eprintln!("[serial] baud readback failed: {err}");
+      None
+    }
+  };
+  if let Some(actual) = actual_baud.filter(|&actual| !baud_within_tolerance(config.baud, actual)) {
+    eprintln!("[serial] baud mismatch requested={} actual={actual}", config.baud);
+    let _ = app.emit(
+      "serial:baud_mismatch",
+      SerialBaudMismatch {
+        port_id: port_id.clone(),
+        port: config.port.clone(),
+        requested: config.baud,
+        actual,
+      },
+    );
+  }
+  let rs485_mode = driver_enable.map(|mode| match mode {
+    DriverEnable::Native => "native".to_string(),
+    DriverEnable::Manual { .. } => "manual".to_string(),
+  });
+
+  let entry = PortEntry {
+    id: port_id.clone(),
+    alias: alias.clone(),
+    port: Mutex::new(Some(port)),
+    driver_enable: Mutex::new(driver_enable),
+    last_config: Mutex::new(Some(config.clone())),
+    opened_instant: Mutex::new(Some(Instant::now())),
+    read_timeout_ms: AtomicU64::new(config.read_timeout_ms),
+    write_timeout_ms: AtomicU64::new(config.write_timeout_ms),
+    ..PortEntry::default()
+  };
+  entry.stats.set_opened(true);
+  state.ports.lock()?.insert(port_id.clone(), Arc::new(entry));
+  eprintln!(
+    "[serial] open ok port_id={} port={} baud={} parity={} stop_bits={} data_bits={} read_timeout_ms={} write_timeout_ms={} fd={:?} handle={:?}",
+    port_id,
+    config.port,
+    config.baud,
+    config.parity,
+    config.stop_bits,
+    config.data_bits,
+    config.read_timeout_ms,
+    config.write_timeout_ms,
     fd,
     handle
   );
+  // Remember the alias rather than today's path so reopening follows the device.
+  let recent = SerialConfig {
+    port: alias.clone().unwrap_or_else(|| config.port.clone()),
+    ..config.clone()
+  };
+  if let Err(err) = crate::recent_ports::record_recent_port(&app, &recent) {
+    eprintln!("[serial] recent ports update failed: {err}");
+  }
+  port_state_changed(&app);
   Ok(SerialStatus {
+    port_id,
     port: config.port,
+    alias,
     baud: config.baud,
+    actual_baud,
     parity: config.parity,
     stop_bits: config.stop_bits,
     data_bits: config.data_bits,
-    timeout_ms,
+    read_timeout_ms: config.read_timeout_ms,
+    write_timeout_ms: config.write_timeout_ms,
     fd,
     handle,
+    rs485_mode,
   })
 }
 
+/// Closes `port_id` (or the only open port); closing an unknown id is a no-op.
 #[tauri::command]
-pub fn close_serial_port(state: State<SerialState>) -> Result<(), String> {
-  let mut guard = state.port.lock().map_err(|_| "Serial port mutex poisoned".to_string())?;
-  *guard = None;
-  eprintln!("[serial] close ok");
+pub fn close_serial_port<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+) -> Result<(), SerialError> {
+  let entry = match state.entry(port_id.as_deref()) {
+    Ok(entry) => entry,
+    Err(SerialError::NotOpen) => return Ok(()),
+    Err(err) => return Err(err),
+  };
+  state.ports.lock()?.remove(&entry.id);
+  entry.shutdown()?;
+  eprintln!("[serial] close ok port_id={}", entry.id);
+  port_state_changed(&app);
   Ok(())
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenSerialPort {
+  pub port_id: String,
+  pub port: String,
+  pub alias: Option<String>,
+  pub baud: u32,
+  pub streaming: bool,
+  pub reconnecting: bool,
+}
+
+/// Ports currently open, ordered by id; a port waiting to reconnect is listed
+/// with `reconnecting: true`.
+#[tauri::command]
+pub fn open_serial_ports(state: State<SerialState>) -> Vec<OpenSerialPort> {
+  state
+    .entries()
+    .into_iter()
+    .filter_map(|entry| {
+      let config = entry.last_config.lock().ok()?.clone()?;
+      Some(OpenSerialPort {
+        port_id: entry.id.clone(),
+        port: config.port,
+        alias: entry.alias.clone(),
+        baud: config.baud,
+        streaming: stream_active(&entry).unwrap_or(false),
+        reconnecting: entry.reconnecting.load(Ordering::SeqCst),
+      })
+    })
+    .collect()
+}
+
+/// Writes `bytes` and flushes. With an inter-byte delay the bytes go out one at
+/// a time, holding the port mutex for the whole frame; that's fine for the
+/// slow receivers this exists for.
+fn write_paced(
+  port: &mut dyn SerialIo,
+  bytes: &[u8],
+  inter_byte_delay_us: Option<u64>,
+) -> io::Result<()> {
+  match inter_byte_delay_us.filter(|&us| us > 0) {
+    Some(us) => {
+      for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+          thread::sleep(Duration::from_micros(us));
+        }
+        port.write_all(std::slice::from_ref(byte))?;
+      }
+    }
+    None => port.write_all(bytes)?,
+  }
+  port.flush()
+}
+
+/// Turns command input into wire bytes: hex/base64 are decoded, text gets `line_ending`.
+pub(crate) fn encode_payload(
+  data: String,
+  format: Option<&str>,
+  line_ending: Option<&str>,
+) -> Result<Vec<u8>, SerialError> {
+  match format {
+    Some("hex") => hex_to_bytes(&data),
+    Some("base64") => base64_to_bytes(&data),
+    _ => {
+      let mut bytes = data.into_bytes();
+      bytes.extend_from_slice(parse_line_ending(line_ending)?);
+      Ok(bytes)
+    }
+  }
+}
+
+/// Writes one frame, toggling RTS around it when driver-enable is manual.
+pub(crate) fn write_frame(
+  port: &mut dyn SerialIo,
+  bytes: &[u8],
+  driver_enable: Option<DriverEnable>,
+  inter_byte_delay_us: Option<u64>,
+) -> io::Result<()> {
+  match driver_enable {
+    Some(DriverEnable::Manual {
+      active_high,
+      post_tx_delay_us,
+    }) => {
+      port.write_request_to_send(active_high)?;
+      let result = write_paced(port, bytes, inter_byte_delay_us);
+      // flush() only means the OS buffer drained; let the UART shift out the last byte.
+      if result.is_ok() && post_tx_delay_us > 0 {
+        thread::sleep(Duration::from_micros(post_tx_delay_us));
+      }
+      let reset = port.write_request_to_send(!active_high);
+      result.and_then(|_| reset.map_err(io::Error::from))
+    }
+    _ => write_paced(port, bytes, inter_byte_delay_us),
+  }
+}
+
 #[tauri::command]
-pub fn write_serial_data(
+pub fn write_serial_data<R: Runtime>(
+  app: AppHandle<R>,
   state: State<SerialState>,
+  port_id: Option<String>,
   data: String,
   format: Option<String>,
-) -> Result<usize, String> {
-  let mut guard = state.port.lock().map_err(|_| "Serial port mutex poisoned".to_string())?;
-  let port = guard.as_mut().ok_or_else(|| "Serial port not open".to_string())?;
-  let bytes = match format.as_deref() {
-    Some("hex") => hex_to_bytes(&data)?,
-    _ => data.into_bytes(),
-  };
+  line_ending: Option<String>,
+  inter_byte_delay_us: Option<u64>,
+) -> Result<usize, SerialError> {
+  let bytes = encode_payload(data, format.as_deref(), line_ending.as_deref())?;
+  if let Some(us) = inter_byte_delay_us {
+    if us > MAX_INTER_BYTE_DELAY_US {
+      return Err(SerialError::InvalidInput(format!(
+        "Inter-byte delay of {us} us exceeds the {MAX_INTER_BYTE_DELAY_US} us maximum"
+      )));
+    }
+    let total_ms = us * bytes.len().saturating_sub(1) as u64 / 1000;
+    if total_ms > MAX_PACED_WRITE_MS {
+      return Err(SerialError::InvalidInput(format!(
+        "Pacing {} bytes {us} us apart takes {total_ms} ms, over the \
+         {MAX_PACED_WRITE_MS} ms maximum",
+        bytes.len()
+      )));
+    }
+  }
+  let state = state.entry(port_id.as_deref())?;
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  let driver_enable = *state.driver_enable.lock()?;
+  use_timeout(port.as_mut(), &state.write_timeout_ms)?;
 
-  port.write_all(&bytes).map_err(|err| err.to_string())?;
-  port.flush().map_err(|err| err.to_string())?;
+  let result = write_frame(port.as_mut(), &bytes, driver_enable, inter_byte_delay_us);
+  drop(guard);
+  if let Err(err) = result {
+    if err.kind() == ErrorKind::TimedOut {
+      state.stats.record_timeout();
+    }
+    return Err(io_failure(&app, &state, err));
+  }
+  state.stats.record_write(bytes.len());
   eprintln!("[serial] write ok bytes={}", bytes.len());
   Ok(bytes.len())
 }
 
 #[tauri::command]
-pub fn read_serial_data(
+pub fn read_serial_data<R: Runtime>(
+  app: AppHandle<R>,
   state: State<SerialState>,
+  port_id: Option<String>,
   max_bytes: Option<usize>,
-) -> Result<SerialRead, String> {
-  let mut guard = state.port.lock().map_err(|_| "Serial port mutex poisoned".to_string())?;
-  let port = guard.as_mut().ok_or_else(|| "Serial port not open".to_string())?;
+) -> Result<SerialRead, SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  if stream_active(&state)? {
+    return Err(SerialError::Busy(
+      "Serial stream active; stop it before reading manually".to_string(),
+    ));
+  }
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
   let mut buf = vec![0u8; max_bytes.unwrap_or(1024)];
+  use_timeout(port.as_mut(), &state.read_timeout_ms)?;
 
-  let n = match port.read(&mut buf) {
+  let result = port.read(&mut buf);
+  drop(guard);
+  let n = match result {
     Ok(count) => count,
-    Err(err) if err.kind() == ErrorKind::TimedOut => 0,
-    Err(err) => return Err(err.to_string()),
+    Err(err) => {
+      state.stats.record_read_error(&err);
+      if err.kind() != ErrorKind::TimedOut {
+        return Err(io_failure(&app, &state, err));
+      }
+      0
+    }
   };
+  state.stats.record_read(n);
 
   buf.truncate(n);
-  let text = String::from_utf8_lossy(&buf).to_string();
-  let hex = bytes_to_hex(&buf);
   eprintln!("[serial] read ok bytes={}", n);
-  Ok(SerialRead { len: n, text, hex })
+  Ok(SerialRead::from_bytes(&buf))
+}
+
+/// Non-blocking read: returns only what is already buffered, or `len: 0`
+/// immediately when nothing is waiting.
+#[tauri::command]
+pub fn read_serial_available<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+  max_bytes: Option<usize>,
+) -> Result<SerialRead, SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  if stream_active(&state)? {
+    return Err(SerialError::Busy(
+      "Serial stream active; stop it before reading manually".to_string(),
+    ));
+  }
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  let pending = port.bytes_to_read()? as usize;
+  let mut buf = vec![0u8; pending.min(max_bytes.unwrap_or(1024))];
+  if buf.is_empty() {
+    return Ok(SerialRead::from_bytes(&buf));
+  }
+  use_timeout(port.as_mut(), &state.read_timeout_ms)?;
+
+  let result = port.read(&mut buf);
+  drop(guard);
+  let n = match result {
+    Ok(count) => count,
+    Err(err) => {
+      state.stats.record_read_error(&err);
+      if err.kind() != ErrorKind::TimedOut {
+        return Err(io_failure(&app, &state, err));
+      }
+      0
+    }
+  };
+  state.stats.record_read(n);
+  buf.truncate(n);
+  Ok(SerialRead::from_bytes(&buf))
+}
+
+#[tauri::command]
+pub fn start_serial_stream<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+  chunk_hint: Option<usize>,
+) -> Result<(), SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  {
+    let guard = state.port.lock()?;
+    if guard.is_none() {
+      return Err(SerialError::NotOpen);
+    }
+  }
+
+  let mut stream = state.stream.lock()?;
+  if stream.as_ref().is_some_and(SerialStream::is_running) {
+    return Err(SerialError::Busy("Serial stream already running".to_string()));
+  }
+
+  let chunk_size = chunk_hint.unwrap_or(1024).clamp(1, 65536);
+  let entry = state.clone();
+  *stream = Some(SerialStream::spawn(move |stop| run_stream(app, entry, stop, chunk_size)));
+  eprintln!("[serial] stream started port_id={} chunk_size={chunk_size}", state.id);
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_serial_stream(
+  state: State<SerialState>,
+  port_id: Option<String>,
+) -> Result<(), SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  stop_stream(&state)
+}
+
+#[tauri::command]
+pub fn set_serial_dtr(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  level: bool,
+) -> Result<(), SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  port.write_data_terminal_ready(level)?;
+  eprintln!("[serial] dtr={level}");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn set_serial_rts(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  level: bool,
+) -> Result<(), SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  port.write_request_to_send(level)?;
+  eprintln!("[serial] rts={level}");
+  Ok(())
+}
+
+/// Lowers DTR, waits `ms` (at most 10 s), then raises it again
+/// (Arduino-style reset). The port lock is released while waiting so other
+/// I/O is not blocked.
+#[tauri::command]
+pub fn pulse_serial_dtr(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  ms: u64,
+) -> Result<(), SerialError> {
+  if ms > MAX_DTR_PULSE_MS {
+    return Err(SerialError::InvalidInput(format!(
+      "DTR pulse of {ms} ms exceeds the {MAX_DTR_PULSE_MS} ms maximum"
+    )));
+  }
+  set_serial_dtr(state.clone(), port_id.clone(), false)?;
+  thread::sleep(Duration::from_millis(ms));
+  set_serial_dtr(state, port_id, true)
+}
+
+#[tauri::command]
+pub fn read_serial_lines(
+  state: State<SerialState>,
+  port_id: Option<String>,
+) -> Result<SerialLines, SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  Ok(SerialLines {
+    cts: port.read_clear_to_send()?,
+    dsr: port.read_data_set_ready()?,
+    carrier_detect: port.read_carrier_detect()?,
+    ring: port.read_ring_indicator()?,
+  })
+}
+
+/// Holds the TX line in the break state for `duration_ms` (capped at 5 s).
+/// The port stays locked for the duration so nothing is written mid-break.
+#[tauri::command]
+pub fn send_serial_break(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  duration_ms: u64,
+) -> Result<(), SerialError> {
+  if duration_ms == 0 {
+    return Err(SerialError::InvalidInput(
+      "Break duration must be greater than zero".to_string(),
+    ));
+  }
+  let duration_ms = duration_ms.min(MAX_BREAK_MS);
+
+  let state = state.entry(port_id.as_deref())?;
+  let guard = state.port.lock()?;
+  let port = guard.as_ref().ok_or(SerialError::NotOpen)?;
+  port.set_break()?;
+  thread::sleep(Duration::from_millis(duration_ms));
+  port.clear_break()?;
+  eprintln!("[serial] break sent duration_ms={duration_ms}");
+  Ok(())
+}
+
+/// How far past its deadline a `read_until` read may block, so the port
+/// timeout is only re-set every few milliseconds instead of per byte.
+const READ_UNTIL_SLACK: Duration = Duration::from_millis(10);
+
+/// Pulls bytes into `buf` until it ends with `delimiter` (returns `true`),
+/// holds `max_bytes`, or `deadline` passes. An empty delimiter never
+/// matches. Leaves the port timeout modified.
+fn read_until(
+  port: &mut dyn SerialIo,
+  delimiter: &[u8],
+  max_bytes: usize,
+  deadline: Instant,
+  buf: &mut Vec<u8>,
+) -> io::Result<bool> {
+  let mut byte = [0u8; 1];
+  while buf.len() < max_bytes {
+    let now = Instant::now();
+    if now >= deadline {
+      break;
+    }
+    let remaining = deadline - now;
+    let timeout = port.timeout();
+    if timeout < remaining || timeout > remaining + READ_UNTIL_SLACK {
+      port.set_timeout(remaining)?;
+    }
+    match port.read(&mut byte) {
+      Ok(0) => continue,
+      Ok(_) => {
+        buf.push(byte[0]);
+        if !delimiter.is_empty() && buf.ends_with(delimiter) {
+          return Ok(true);
+        }
+      }
+      Err(err) if err.kind() == ErrorKind::TimedOut => break,
+      Err(err) => return Err(err),
+    }
+  }
+  Ok(false)
+}
+
+/// Reads until `delimiter` is seen, `max_bytes` are collected, or `timeout_ms`
+/// elapses. Bytes are pulled one at a time so nothing past the delimiter is
+/// consumed; on timeout the partial frame is returned with `terminated: false`.
+#[tauri::command]
+pub fn read_serial_until<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+  delimiter: String,
+  delimiter_format: Option<String>,
+  max_bytes: usize,
+  timeout_ms: u64,
+) -> Result<SerialReadUntil, SerialError> {
+  let delimiter = match delimiter_format.as_deref() {
+    Some("hex") => hex_to_bytes(&delimiter)?,
+    _ => delimiter.into_bytes(),
+  };
+  if delimiter.is_empty() {
+    return Err(SerialError::InvalidInput("Delimiter must not be empty".to_string()));
+  }
+  let state = state.entry(port_id.as_deref())?;
+  if stream_active(&state)? {
+    return Err(SerialError::Busy(
+      "Serial stream active; stop it before reading manually".to_string(),
+    ));
+  }
+
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  let original_timeout = port.timeout();
+  let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+  let mut buf = Vec::new();
+  let result = read_until(port.as_mut(), &delimiter, max_bytes, deadline, &mut buf);
+  let restored = port.set_timeout(original_timeout);
+  drop(guard);
+  state.stats.record_read(buf.len());
+  let terminated = match result {
+    Ok(terminated) => terminated,
+    Err(err) => {
+      state.stats.record_read_error(&err);
+      return Err(io_failure(&app, &state, err));
+    }
+  };
+  restored?;
+
+  eprintln!("[serial] read_until ok bytes={} terminated={terminated}", buf.len());
+  Ok(SerialReadUntil {
+    read: SerialRead::from_bytes(&buf),
+    terminated,
+  })
+}
+
+/// Switches the live port to `baud` without reopening it, so RTS state and
+/// buffered bytes survive. Returns the baud the driver actually applied.
+#[tauri::command]
+pub fn set_serial_baud(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  baud: u32,
+) -> Result<u32, SerialError> {
+  if baud == 0 {
+    return Err(SerialError::InvalidConfig("Baud rate must be greater than zero".to_string()));
+  }
+
+  let state = state.entry(port_id.as_deref())?;
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  port.set_baud_rate(baud)?;
+  let effective = port.baud_rate()?;
+  // Reconnects should come back at the new rate, not the one originally opened with.
+  if let Some(config) = state.last_config.lock()?.as_mut() {
+    config.baud = baud;
+  }
+  eprintln!("[serial] baud changed requested={baud} effective={effective}");
+  Ok(effective)
+}
+
+/// Discards buffered bytes (`which` is `"input"`, `"output"`, or `"both"`).
+/// Returns how many bytes were waiting to be read before the clear.
+#[tauri::command]
+pub fn clear_serial_buffer(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  which: String,
+) -> Result<u32, SerialError> {
+  let buffer = match which.as_str() {
+    "input" => serialport::ClearBuffer::Input,
+    "output" => serialport::ClearBuffer::Output,
+    "both" => serialport::ClearBuffer::All,
+    other => {
+      return Err(SerialError::InvalidInput(format!("Unsupported buffer: {other}")));
+    }
+  };
+
+  let state = state.entry(port_id.as_deref())?;
+  let guard = state.port.lock()?;
+  let port = guard.as_ref().ok_or(SerialError::NotOpen)?;
+  let discarded = match buffer {
+    serialport::ClearBuffer::Output => 0,
+    _ => port.bytes_to_read().unwrap_or(0),
+  };
+  port.clear(buffer)?;
+  eprintln!("[serial] cleared buffer={which} discarded={discarded}");
+  Ok(discarded)
+}
+
+#[tauri::command]
+pub fn serial_bytes_available(
+  state: State<SerialState>,
+  port_id: Option<String>,
+) -> Result<SerialAvail, SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  let guard = state.port.lock()?;
+  let port = guard.as_ref().ok_or(SerialError::NotOpen)?;
+  // Some drivers (and non-desktop targets) can't report queue depth.
+  let unsupported = |err: serialport::Error| {
+    SerialError::Io(format!("Buffer status not supported by this port: {err}"))
+  };
+  let to_read = port.bytes_to_read().map_err(unsupported)?;
+  let to_write = port.bytes_to_write().map_err(unsupported)?;
+  Ok(SerialAvail { to_read, to_write })
+}
+
+/// Counters for `port_id`; all zero when that port isn't open.
+#[tauri::command]
+pub fn serial_stats(state: State<SerialState>, port_id: Option<String>) -> SerialStats {
+  match state.entry(port_id.as_deref()) {
+    Ok(entry) => entry.stats.snapshot(),
+    Err(_) => SerialCounters::default().snapshot(),
+  }
+}
+
+/// Zeroes the counters; uptime keeps counting from the original open.
+#[tauri::command]
+pub fn reset_serial_stats(state: State<SerialState>, port_id: Option<String>) {
+  if let Ok(entry) = state.entry(port_id.as_deref()) {
+    entry.stats.reset();
+  }
+}
+
+fn port_names() -> BTreeSet<String> {
+  serialport::available_ports()
+    .map(|ports| ports.into_iter().map(|port| port.port_name).collect())
+    .unwrap_or_default()
+}
+
+fn run_port_watch<R: Runtime>(app: AppHandle<R>, stop: Arc<AtomicBool>, interval: Duration) {
+  let settle = Duration::from_millis(PORT_WATCH_SETTLE_MS);
+  let mut reported = port_names();
+  // Ports whose presence differs from `reported`, keyed to when that was first seen.
+  let mut pending: HashMap<String, Instant> = HashMap::new();
+
+  while !stop.load(Ordering::Relaxed) {
+    let started = Instant::now();
+    while started.elapsed() < interval && !stop.load(Ordering::Relaxed) {
+      thread::sleep(Duration::from_millis(50));
+    }
+    if stop.load(Ordering::Relaxed) {
+      break;
+    }
+
+    let current = port_names();
+    let changed: BTreeSet<String> = current.symmetric_difference(&reported).cloned().collect();
+    pending.retain(|name, _| changed.contains(name));
+    for name in &changed {
+      pending.entry(name.clone()).or_insert_with(Instant::now);
+    }
+
+    let settled: Vec<String> = pending
+      .iter()
+      .filter(|(_, since)| since.elapsed() >= settle)
+      .map(|(name, _)| name.clone())
+      .collect();
+    let (added, removed): (Vec<String>, Vec<String>) =
+      settled.into_iter().partition(|name| current.contains(name));
+    for name in added.iter().chain(&removed) {
+      pending.remove(name);
+    }
+    if !added.is_empty() {
+      eprintln!("[serial] ports added {added:?}");
+      reported.extend(added.iter().cloned());
+      let _ = app.emit("serial:port_added", added);
+    }
+    if !removed.is_empty() {
+      eprintln!("[serial] ports removed {removed:?}");
+      for name in &removed {
+        reported.remove(name);
+      }
+      let _ = app.emit("serial:port_removed", removed);
+    }
+  }
+}
+
+/// Polls `available_ports()` every `interval_ms` (default 1000) and emits
+/// `serial:port_added` / `serial:port_removed` with the affected names.
+#[tauri::command]
+pub fn start_port_watch<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  interval_ms: Option<u64>,
+) -> Result<(), SerialError> {
+  let mut watch = state.port_watch.lock()?;
+  if watch.as_ref().is_some_and(SerialStream::is_running) {
+    return Err(SerialError::Busy("Port watch already running".to_string()));
+  }
+
+  let interval = Duration::from_millis(interval_ms.unwrap_or(1000).max(200));
+  *watch = Some(SerialStream::spawn(move |stop| run_port_watch(app, stop, interval)));
+  eprintln!("[serial] port watch started interval_ms={}", interval.as_millis());
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_port_watch(state: State<SerialState>) -> Result<(), SerialError> {
+  let watch = state.port_watch.lock()?.take();
+  if let Some(watch) = watch {
+    watch.stop();
+    eprintln!("[serial] port watch stopped");
+  }
+  Ok(())
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaudProbe {
+  pub baud: u32,
+  pub bytes: usize,
+  pub score: f64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaudDetection {
+  /// Best-scoring rate, or `None` if nothing intelligible came back.
+  pub baud: Option<u32>,
+  pub confidence: f64,
+  pub probes: Vec<BaudProbe>,
+}
+
+/// Heuristic 0..1 score: share of printable/whitespace ASCII, discounted for
+/// very short replies. Wrong rates mostly yield 0x00/0xFF and high-bit noise.
+fn score_baud_response(bytes: &[u8]) -> f64 {
+  if bytes.is_empty() {
+    return 0.0;
+  }
+  let printable = bytes
+    .iter()
+    .filter(|&&b| b.is_ascii_graphic() || matches!(b, b' ' | b'\r' | b'\n' | b'\t'))
+    .count();
+  let ratio = printable as f64 / bytes.len() as f64;
+  ratio * (bytes.len() as f64 / 8.0).min(1.0)
+}
+
+/// Best-effort baud detection: for each candidate, switch the open port,
+/// send `probe` (or just listen when empty), collect replies for
+/// `timeout_ms`, and score how text-like they look. This is a heuristic for
+/// text protocols, not a guarantee; the port's original baud is restored
+/// afterwards. Emits `serial:baud_probe` per candidate.
+#[tauri::command]
+pub fn detect_serial_baud<R: Runtime>(
+  app: AppHandle<R>,
+  state: State<SerialState>,
+  port_id: Option<String>,
+  candidates: Vec<u32>,
+  probe: String,
+  timeout_ms: u64,
+) -> Result<BaudDetection, SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  if stream_active(&state)? {
+    return Err(SerialError::Busy(
+      "Serial stream active; stop it before detecting baud".to_string(),
+    ));
+  }
+  let candidates: Vec<u32> = if candidates.is_empty() {
+    DEFAULT_BAUD_CANDIDATES.to_vec()
+  } else {
+    candidates.into_iter().filter(|&baud| baud > 0).collect()
+  };
+
+  let mut guard = state.port.lock()?;
+  let port = guard.as_mut().ok_or(SerialError::NotOpen)?;
+  let original_baud = port.baud_rate()?;
+  let original_timeout = port.timeout();
+  let mut probes = Vec::with_capacity(candidates.len());
+
+  let result = (|| -> Result<(), SerialError> {
+    for &baud in &candidates {
+      port.set_baud_rate(baud)?;
+      port.clear(serialport::ClearBuffer::All)?;
+      if !probe.is_empty() {
+        write_paced(port.as_mut(), probe.as_bytes(), None)?;
+      }
+      let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+      let mut buf = Vec::new();
+      // An empty delimiter never matches, so this collects until the deadline.
+      read_until(port.as_mut(), &[], 256, deadline, &mut buf)?;
+      let probe = BaudProbe {
+        baud,
+        bytes: buf.len(),
+        score: score_baud_response(&buf),
+      };
+      eprintln!("[serial] baud probe baud={baud} bytes={} score={:.2}", probe.bytes, probe.score);
+      let _ = app.emit("serial:baud_probe", probe.clone());
+      probes.push(probe);
+    }
+    Ok(())
+  })();
+  let restored = port
+    .set_baud_rate(original_baud)
+    .and_then(|_| port.set_timeout(original_timeout));
+  result?;
+  restored?;
+
+  let best = probes
+    .iter()
+    .filter(|probe| probe.score > 0.0)
+    .max_by(|a, b| a.score.total_cmp(&b.score));
+  Ok(BaudDetection {
+    baud: best.map(|probe| probe.baud),
+    confidence: best.map(|probe| probe.score).unwrap_or(0.0),
+    probes,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::serial_io::MockSerial;
+
+  #[test]
+  fn hex_write_sends_decoded_bytes() {
+    let mut port = MockSerial::new(&[]);
+    let bytes = encode_payload("01 03 00 00 00 0A".to_string(), Some("hex"), Some("crlf")).unwrap();
+    write_frame(&mut port, &bytes, None, None).unwrap();
+    assert_eq!(port.written, [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A]);
+  }
+
+  #[test]
+  fn manual_driver_enable_releases_rts_after_write() {
+    let mut port = MockSerial::new(&[]);
+    let de = Some(DriverEnable::Manual {
+      active_high: true,
+      post_tx_delay_us: 0,
+    });
+    write_frame(&mut port, b"AT", de, None).unwrap();
+    assert_eq!(port.written, b"AT");
+    assert!(!port.rts);
+  }
+
+  #[test]
+  fn parses_one_and_a_half_stop_bits() {
+    assert_eq!(parse_stop_bits("1.5").unwrap(), StopBits::OnePointFive);
+    assert_eq!(parse_stop_bits("1").unwrap(), StopBits::One);
+    assert_eq!(parse_stop_bits("2").unwrap(), StopBits::Two);
+    assert!(matches!(parse_stop_bits("3"), Err(SerialError::InvalidConfig(_))));
+
+    // 1.5 only exists alongside 5 data bits.
+    assert!(stop_bits_setting(StopBits::OnePointFive, 8).is_err());
+    #[cfg(unix)]
+    assert_eq!(stop_bits_setting(StopBits::OnePointFive, 5).unwrap(), serialport::StopBits::Two);
+    #[cfg(not(unix))]
+    assert!(stop_bits_setting(StopBits::OnePointFive, 5).is_err());
+  }
+
+  #[test]
+  fn read_until_stops_at_delimiter() {
+    let mut port = MockSerial::new(b"OK\r\nNEXT");
+    port.timeout = Duration::ZERO;
+    let mut buf = Vec::new();
+    let deadline = Instant::now() + Duration::from_millis(100);
+    let terminated = read_until(&mut port, b"\r\n", 64, deadline, &mut buf).unwrap();
+    assert!(terminated);
+    assert_eq!(buf, b"OK\r\n");
+    assert_eq!(port.rx, b"NEXT");
+    // One timeout for the whole frame, not one per byte.
+    assert_eq!(port.timeout_sets, 1);
+  }
+
+  #[test]
+  fn read_until_returns_partial_frame_on_timeout() {
+    let mut port = MockSerial::new(b"PARTIAL");
+    let mut buf = Vec::new();
+    let deadline = Instant::now() + Duration::from_millis(100);
+    let terminated = read_until(&mut port, b"\n", 64, deadline, &mut buf).unwrap();
+    assert!(!terminated);
+    assert_eq!(buf, b"PARTIAL");
+  }
 }
@@ -0,0 +1,219 @@
+//! Port abstraction the serial and Modbus commands talk through.
+//! Real ports use the serialport implementation; tests use `MockSerial`.
+
+use std::{
+  io::{Read, Write},
+  time::Duration,
+};
+
+use serialport::ClearBuffer;
+
+/// The subset of `serialport::SerialPort` the commands rely on.
+pub trait SerialIo: Read + Write + Send {
+  fn timeout(&self) -> Duration;
+  fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()>;
+  fn baud_rate(&self) -> serialport::Result<u32>;
+  fn set_baud_rate(&mut self, baud: u32) -> serialport::Result<()>;
+  fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()>;
+  fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()>;
+  fn read_clear_to_send(&mut self) -> serialport::Result<bool>;
+  fn read_data_set_ready(&mut self) -> serialport::Result<bool>;
+  fn read_carrier_detect(&mut self) -> serialport::Result<bool>;
+  fn read_ring_indicator(&mut self) -> serialport::Result<bool>;
+  fn bytes_to_read(&self) -> serialport::Result<u32>;
+  fn bytes_to_write(&self) -> serialport::Result<u32>;
+  fn clear(&self, buffer: ClearBuffer) -> serialport::Result<()>;
+  fn set_break(&self) -> serialport::Result<()>;
+  fn clear_break(&self) -> serialport::Result<()>;
+}
+
+impl<T: serialport::SerialPort> SerialIo for T {
+  fn timeout(&self) -> Duration {
+    serialport::SerialPort::timeout(self)
+  }
+
+  fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+    serialport::SerialPort::set_timeout(self, timeout)
+  }
+
+  fn baud_rate(&self) -> serialport::Result<u32> {
+    serialport::SerialPort::baud_rate(self)
+  }
+
+  fn set_baud_rate(&mut self, baud: u32) -> serialport::Result<()> {
+    serialport::SerialPort::set_baud_rate(self, baud)
+  }
+
+  fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+    serialport::SerialPort::write_request_to_send(self, level)
+  }
+
+  fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+    serialport::SerialPort::write_data_terminal_ready(self, level)
+  }
+
+  fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+    serialport::SerialPort::read_clear_to_send(self)
+  }
+
+  fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+    serialport::SerialPort::read_data_set_ready(self)
+  }
+
+  fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+    serialport::SerialPort::read_carrier_detect(self)
+  }
+
+  fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+    serialport::SerialPort::read_ring_indicator(self)
+  }
+
+  fn bytes_to_read(&self) -> serialport::Result<u32> {
+    serialport::SerialPort::bytes_to_read(self)
+  }
+
+  fn bytes_to_write(&self) -> serialport::Result<u32> {
+    serialport::SerialPort::bytes_to_write(self)
+  }
+
+  fn clear(&self, buffer: ClearBuffer) -> serialport::Result<()> {
+    serialport::SerialPort::clear(self, buffer)
+  }
+
+  fn set_break(&self) -> serialport::Result<()> {
+    serialport::SerialPort::set_break(self)
+  }
+
+  fn clear_break(&self) -> serialport::Result<()> {
+    serialport::SerialPort::clear_break(self)
+  }
+}
+
+/// In-memory port: reads drain `rx` (timing out when empty), writes append to `written`.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct MockSerial {
+  pub rx: std::collections::VecDeque<u8>,
+  pub written: Vec<u8>,
+  pub timeout: Duration,
+  pub baud: u32,
+  pub rts: bool,
+  pub dtr: bool,
+  /// RTS level at each `write` call, to check driver-enable timing.
+  pub rts_at_write: Vec<bool>,
+  /// Number of `set_timeout` calls.
+  pub timeout_sets: usize,
+}
+
+#[cfg(test)]
+impl MockSerial {
+  pub fn new(rx: &[u8]) -> Self {
+    Self {
+      rx: rx.iter().copied().collect(),
+      written: Vec::new(),
+      timeout: Duration::from_millis(100),
+      baud: 9600,
+      rts: false,
+      dtr: false,
+      rts_at_write: Vec::new(),
+      timeout_sets: 0,
+    }
+  }
+}
+
+#[cfg(test)]
+impl Read for MockSerial {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    if self.rx.is_empty() {
+      return Err(std::io::ErrorKind::TimedOut.into());
+    }
+    let n = buf.len().min(self.rx.len());
+    for (slot, byte) in buf.iter_mut().zip(self.rx.drain(..n)) {
+      *slot = byte;
+    }
+    Ok(n)
+  }
+}
+
+#[cfg(test)]
+impl Write for MockSerial {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.written.extend_from_slice(buf);
+    self.rts_at_write.push(self.rts);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+impl SerialIo for MockSerial {
+  fn timeout(&self) -> Duration {
+    self.timeout
+  }
+
+  fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+    self.timeout = timeout;
+    self.timeout_sets += 1;
+    Ok(())
+  }
+
+  fn baud_rate(&self) -> serialport::Result<u32> {
+    Ok(self.baud)
+  }
+
+  fn set_baud_rate(&mut self, baud: u32) -> serialport::Result<()> {
+    self.baud = baud;
+    Ok(())
+  }
+
+  fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+    self.rts = level;
+    Ok(())
+  }
+
+  fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+    self.dtr = level;
+    Ok(())
+  }
+
+  fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+    Ok(self.rts)
+  }
+
+  fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+    Ok(self.dtr)
+  }
+
+  fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+    Ok(false)
+  }
+
+  fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+    Ok(false)
+  }
+
+  fn bytes_to_read(&self) -> serialport::Result<u32> {
+    Ok(self.rx.len() as u32)
+  }
+
+  fn bytes_to_write(&self) -> serialport::Result<u32> {
+    Ok(0)
+  }
+
+  fn clear(&self, _buffer: ClearBuffer) -> serialport::Result<()> {
+    // Scripted RX is kept so a test can queue a reply ahead of a
+    // transaction that clears input before writing (e.g. Modbus).
+    Ok(())
+  }
+
+  fn set_break(&self) -> serialport::Result<()> {
+    Ok(())
+  }
+
+  fn clear_break(&self) -> serialport::Result<()> {
+    Ok(())
+  }
+}
@@ -0,0 +1,237 @@
+//! Named serial configuration presets for devices operators switch between.
+//! The whole set can be exported and imported as one versioned JSON profile.
+
+use std::{collections::BTreeMap, path::Path};
+
+use tauri::{AppHandle, Runtime};
+
+use crate::json_store::{self, JsonStore};
+use crate::serial::{SerialConfig, SerialError};
+
+/// Bump when the profile layout changes incompatibly.
+const PROFILE_VERSION: u32 = 1;
+
+static PRESETS: JsonStore = JsonStore::new("serial_presets.json", "presets");
+
+type Presets = BTreeMap<String, SerialConfig>;
+
+/// Shareable document holding every saved preset.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialProfile {
+  version: u32,
+  presets: Presets,
+}
+
+fn validate_name(name: &str) -> Result<&str, SerialError> {
+  json_store::validate_name("preset name", name)
+}
+
+#[tauri::command]
+pub fn save_serial_preset<R: Runtime>(
+  app: AppHandle<R>,
+  name: String,
+  config: SerialConfig,
+) -> Result<(), SerialError> {
+  let name = validate_name(&name)?;
+  config.validate()?;
+  let path = PRESETS.path(&app)?;
+  let _guard = PRESETS.lock()?;
+  let mut presets: Presets = PRESETS.read(&path);
+  presets.insert(name.to_string(), config);
+  PRESETS.write(&path, &presets)?;
+  eprintln!("[serial] preset saved name={name}");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn load_serial_preset<R: Runtime>(
+  app: AppHandle<R>,
+  name: String,
+) -> Result<SerialConfig, SerialError> {
+  let name = validate_name(&name)?;
+  let path = PRESETS.path(&app)?;
+  let _guard = PRESETS.lock()?;
+  let mut presets: Presets = PRESETS.read(&path);
+  presets
+    .remove(name)
+    .ok_or_else(|| SerialError::InvalidInput(format!("Unknown preset: {name}")))
+}
+
+/// Preset names in alphabetical order.
+#[tauri::command]
+pub fn list_serial_presets<R: Runtime>(app: AppHandle<R>) -> Result<Vec<String>, SerialError> {
+  let path = PRESETS.path(&app)?;
+  let _guard = PRESETS.lock()?;
+  Ok(PRESETS.read::<Presets>(&path).into_keys().collect())
+}
+
+/// Returns whether a preset was removed.
+#[tauri::command]
+pub fn delete_serial_preset<R: Runtime>(
+  app: AppHandle<R>,
+  name: String,
+) -> Result<bool, SerialError> {
+  let name = validate_name(&name)?;
+  let path = PRESETS.path(&app)?;
+  let _guard = PRESETS.lock()?;
+  let mut presets: Presets = PRESETS.read(&path);
+  if presets.remove(name).is_none() {
+    return Ok(false);
+  }
+  PRESETS.write(&path, &presets)?;
+  eprintln!("[serial] preset deleted name={name}");
+  Ok(true)
+}
+
+/// Serializes all saved presets into one versioned JSON profile.
+#[tauri::command]
+pub fn export_serial_profile<R: Runtime>(app: AppHandle<R>) -> Result<String, SerialError> {
+  let path = PRESETS.path(&app)?;
+  let _guard = PRESETS.lock()?;
+  export_profile(&path)
+}
+
+/// Loads a profile from `export_serial_profile`, either merging into the
+/// saved presets (imported names win) or replacing them. Returns the number
+/// of presets imported.
+#[tauri::command]
+pub fn import_serial_profile<R: Runtime>(
+  app: AppHandle<R>,
+  json: String,
+  merge: bool,
+) -> Result<usize, SerialError> {
+  let path = PRESETS.path(&app)?;
+  let _guard = PRESETS.lock()?;
+  let count = import_profile(&path, &json, merge)?;
+  eprintln!("[serial] profile imported presets={count} merge={merge}");
+  Ok(count)
+}
+
+fn export_profile(path: &Path) -> Result<String, SerialError> {
+  let profile = SerialProfile {
+    version: PROFILE_VERSION,
+    presets: PRESETS.read(path),
+  };
+  serde_json::to_string_pretty(&profile)
+    .map_err(|err| SerialError::Io(format!("Failed to encode profile: {err}")))
+}
+
+fn import_profile(path: &Path, json: &str, merge: bool) -> Result<usize, SerialError> {
+  let invalid =
+    |err: serde_json::Error| SerialError::InvalidInput(format!("Invalid profile: {err}"));
+  let value: serde_json::Value = serde_json::from_str(json).map_err(invalid)?;
+  match value.get("version").and_then(serde_json::Value::as_u64) {
+    Some(version) if version == u64::from(PROFILE_VERSION) => {}
+    Some(version) => {
+      return Err(SerialError::InvalidInput(format!(
+        "Unsupported profile version {version}; this app reads version {PROFILE_VERSION}"
+      )))
+    }
+    None => {
+      return Err(SerialError::InvalidInput(
+        "Profile has no version field; export it again from this app".to_string(),
+      ))
+    }
+  }
+  let profile: SerialProfile = serde_json::from_value(value).map_err(invalid)?;
+  for (name, config) in &profile.presets {
+    if validate_name(name)? != name {
+      return Err(SerialError::InvalidInput(format!(
+        "Invalid preset name \"{name}\": surrounding whitespace"
+      )));
+    }
+    config
+      .validate()
+      .map_err(|err| SerialError::InvalidConfig(format!("Preset \"{name}\": {err}")))?;
+  }
+
+  let count = profile.presets.len();
+  let presets = if merge {
+    let mut presets: Presets = PRESETS.read(path);
+    presets.extend(profile.presets);
+    presets
+  } else {
+    profile.presets
+  };
+  PRESETS.write(path, &presets)?;
+  Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use serde_json::json;
+
+  use super::*;
+
+  fn profile(version: serde_json::Value, name: &str) -> String {
+    let config = json!({
+      "port": "/dev/ttyUSB0",
+      "baud": 19200,
+      "parity": "Even",
+      "stopBits": "1",
+      "dataBits": 8,
+      "readTimeoutMs": 500,
+      "writeTimeoutMs": 500,
+      "rs485De": true,
+      "dePolarity": "low",
+      "postTxDelayUs": 250,
+      "reconnectMaxAttempts": 3,
+    });
+    let mut profile = json!({ "presets": { name: config, "Bench PSU": config } });
+    if !version.is_null() {
+      profile["version"] = version;
+    }
+    profile.to_string()
+  }
+
+  #[test]
+  fn export_import_round_trip_is_lossless() {
+    let dir = std::env::temp_dir().join(format!("rs485-presets-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("serial_presets.json");
+
+    assert_eq!(import_profile(&path, &profile(json!(1), "Meter"), false).unwrap(), 2);
+    let exported = export_profile(&path).unwrap();
+    assert_eq!(import_profile(&path, &exported, false).unwrap(), 2);
+    let reexported = export_profile(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exported, reexported);
+    let exported: serde_json::Value = serde_json::from_str(&exported).unwrap();
+    assert_eq!(exported["presets"]["Meter"]["postTxDelayUs"], 250);
+  }
+
+  #[test]
+  fn import_rejects_invalid_settings() {
+    let path = std::env::temp_dir().join("rs485-presets-never-written.json");
+    let mut bad = serde_json::from_str::<serde_json::Value>(&profile(json!(1), "Meter")).unwrap();
+    bad["presets"]["Meter"]["parity"] = json!("even");
+    let err = import_profile(&path, &bad.to_string(), true).unwrap_err();
+    assert!(err.to_string().contains("Preset \"Meter\": Unsupported parity: even"), "{err}");
+
+    bad["presets"]["Meter"]["parity"] = json!("None");
+    bad["presets"]["Meter"]["stopBits"] = json!("1.5");
+    assert!(import_profile(&path, &bad.to_string(), true).is_err());
+    bad["presets"]["Meter"]["stopBits"] = json!("1");
+    bad["presets"]["Meter"]["dePolarity"] = json!("sideways");
+    assert!(import_profile(&path, &bad.to_string(), true).is_err());
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn import_rejects_bad_versions_and_padded_names() {
+    let path = std::env::temp_dir().join("rs485-presets-never-written.json");
+    for (version, name, message) in [
+      (json!(2), "Meter", "Unsupported profile version 2"),
+      (json!(null), "Meter", "no version field"),
+      (json!(1), " Meter", "surrounding whitespace"),
+    ] {
+      let err = import_profile(&path, &profile(version, name), true).unwrap_err();
+      assert!(err.to_string().contains(message), "{err}");
+    }
+    assert!(!path.exists());
+  }
+}
@@ -0,0 +1,161 @@
+//! Capture and replay of raw serial RX for offline debugging.
+//! Records the stream loop to a binary log and re-emits it as `serial:data` events.
+
+use std::{
+  fs::{self, File},
+  io::{self, BufWriter, Write},
+  path::Path,
+  thread,
+  time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use crate::serial::{bytes_to_hex, SerialChunk, SerialError, SerialState};
+
+/// File header: magic plus format version.
+const RECORD_MAGIC: &[u8; 9] = b"RS485REC\x01";
+/// Accepted `replay_speed` range.
+const MIN_REPLAY_SPEED: f64 = 0.01;
+const MAX_REPLAY_SPEED: f64 = 100.0;
+/// Longest pause replay will sit through between chunks; longer idle gaps
+/// in a recording are shortened to this.
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(60);
+
+/// Appends chunks as `[delay_us: u64 LE][len: u32 LE][bytes]`, where the delay
+/// is measured from the previous chunk (0 for the first).
+pub struct SerialRecorder {
+  writer: BufWriter<File>,
+  last: Option<Instant>,
+}
+
+impl SerialRecorder {
+  fn create(path: &Path) -> io::Result<Self> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(RECORD_MAGIC)?;
+    Ok(Self { writer, last: None })
+  }
+
+  pub(crate) fn record(&mut self, bytes: &[u8]) -> io::Result<()> {
+    let now = Instant::now();
+    let delay_us = self
+      .last
+      .map(|last| now.duration_since(last).as_micros() as u64)
+      .unwrap_or(0);
+    self.last = Some(now);
+    self.writer.write_all(&delay_us.to_le_bytes())?;
+    self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    self.writer.write_all(bytes)
+  }
+
+  pub(crate) fn finish(mut self) -> io::Result<()> {
+    self.writer.flush()
+  }
+}
+
+/// Parses a recording into `(delay_us, bytes)` pairs.
+fn read_recording(path: &Path) -> Result<Vec<(u64, Vec<u8>)>, SerialError> {
+  let data = fs::read(path)?;
+  let truncated = || SerialError::InvalidInput("Recording is truncated".to_string());
+  let mut rest = data
+    .strip_prefix(RECORD_MAGIC.as_slice())
+    .ok_or_else(|| SerialError::InvalidInput("Not a serial recording".to_string()))?;
+
+  let mut chunks = Vec::new();
+  while !rest.is_empty() {
+    if rest.len() < 12 {
+      return Err(truncated());
+    }
+    let (header, tail) = rest.split_at(12);
+    let delay_us = u64::from_le_bytes(header[..8].try_into().unwrap());
+    let len = u32::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+    if tail.len() < len {
+      return Err(truncated());
+    }
+    let (bytes, tail) = tail.split_at(len);
+    chunks.push((delay_us, bytes.to_vec()));
+    rest = tail;
+  }
+  Ok(chunks)
+}
+
+/// Tees everything `port_id`'s stream loop receives into `path` until stopped
+/// or the port is closed.
+#[tauri::command]
+pub fn start_serial_record(
+  state: State<SerialState>,
+  port_id: Option<String>,
+  path: String,
+) -> Result<(), SerialError> {
+  if path.trim().is_empty() {
+    return Err(SerialError::InvalidInput("Recording path is required".to_string()));
+  }
+  let state = state.entry(port_id.as_deref())?;
+  let mut recorder = state.recorder.lock()?;
+  if recorder.is_some() {
+    return Err(SerialError::Busy("Recording already in progress".to_string()));
+  }
+  *recorder = Some(SerialRecorder::create(Path::new(&path))?);
+  eprintln!("[serial] recording port_id={} to {path}", state.id);
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_serial_record(
+  state: State<SerialState>,
+  port_id: Option<String>,
+) -> Result<(), SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  let recorder = state.recorder.lock()?.take();
+  if let Some(recorder) = recorder {
+    recorder.finish()?;
+    eprintln!("[serial] recording stopped");
+  }
+  Ok(())
+}
+
+/// Re-emits a recording as `serial:data` events with the original pacing
+/// scaled by `replay_speed` (2.0 = twice as fast, 0.01 to 100), then
+/// `serial:replay_done`. Gaps are capped at a minute. Returns the number of
+/// chunks scheduled.
+#[tauri::command]
+pub fn replay_serial_file<R: Runtime>(
+  app: AppHandle<R>,
+  path: String,
+  replay_speed: Option<f64>,
+) -> Result<usize, SerialError> {
+  let speed = replay_speed.unwrap_or(1.0);
+  if !(MIN_REPLAY_SPEED..=MAX_REPLAY_SPEED).contains(&speed) {
+    return Err(SerialError::InvalidInput(format!(
+      "Replay speed must be between {MIN_REPLAY_SPEED} and {MAX_REPLAY_SPEED}"
+    )));
+  }
+  let chunks = read_recording(Path::new(&path))?;
+  let count = chunks.len();
+  eprintln!("[serial] replaying {path} chunks={count} speed={speed}");
+
+  thread::spawn(move || {
+    let mut mono_us: u128 = 0;
+    for (seq, (delay_us, bytes)) in chunks.into_iter().enumerate() {
+      let gap = Duration::try_from_secs_f64(delay_us as f64 / 1_000_000.0 / speed)
+        .map_or(MAX_REPLAY_GAP, |gap| gap.min(MAX_REPLAY_GAP));
+      thread::sleep(gap);
+      mono_us += u128::from(delay_us);
+      let chunk = SerialChunk {
+        port_id: None,
+        len: bytes.len(),
+        text: String::from_utf8_lossy(&bytes).to_string(),
+        hex: bytes_to_hex(&bytes),
+        ts: chrono::Utc::now().to_rfc3339(),
+        seq: seq as u64,
+        mono_us,
+      };
+      let _ = app.emit("serial:data", chunk);
+    }
+    let _ = app.emit("serial:replay_done", count);
+  });
+  Ok(count)
+}
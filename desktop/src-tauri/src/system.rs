@@ -1,9 +1,102 @@
 //! System information helpers for the desktop app.
-//! Provides OS-specific system info strings used in the About dialog.
+//! Collects OS-specific system info for the frontend and the About dialog.
 
+#[cfg(all(unix, not(target_os = "macos")))]
 use std::fs;
+use std::path::Path;
 
-#[cfg(unix)]
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Sampling window for per-CPU utilization when the caller doesn't pick one.
+const DEFAULT_CPU_SAMPLE_MS: u64 = 250;
+/// Upper bound so `cpu_load` always returns promptly.
+const MAX_CPU_SAMPLE_MS: u64 = 1000;
+
+/// Host details; fields a platform can't report are `None`.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+  pub os: String,
+  pub kernel: Option<String>,
+  pub arch: String,
+  pub hostname: Option<String>,
+  /// Hardware model identifier (macOS `hw.model`).
+  pub model: Option<String>,
+  pub cpu_model: Option<String>,
+  pub cpu_cores: u32,
+  /// Processor architecture as reported by the OS (Windows).
+  pub cpu_arch: Option<String>,
+  pub mem_total_bytes: Option<u64>,
+  pub is_wsl: bool,
+  pub wsl_distro: Option<String>,
+  /// Space on the partition holding the app data dir.
+  pub disk_free_bytes: Option<u64>,
+  pub disk_total_bytes: Option<u64>,
+  pub uptime_secs: Option<u64>,
+}
+
+/// Live CPU load; fields a platform can't report are `None` or empty.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuLoad {
+  /// 1/5/15-minute load averages (not available on Windows).
+  pub load1: Option<f64>,
+  pub load5: Option<f64>,
+  pub load15: Option<f64>,
+  /// Busy share of all CPUs over the sample window, 0–100.
+  pub usage_percent: Option<f64>,
+  /// Busy share of each core over the same window (Linux only).
+  pub per_core_percent: Vec<f64>,
+  pub sample_ms: u64,
+}
+
+/// Cumulative CPU time counters; only deltas between two reads are meaningful.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CpuTimes {
+  idle: u64,
+  total: u64,
+}
+
+/// Percentage of `after - before` not spent idle, to one decimal.
+fn busy_percent(before: &CpuTimes, after: &CpuTimes) -> Option<f64> {
+  let total = after.total.checked_sub(before.total).filter(|total| *total > 0)?;
+  let idle = after.idle.saturating_sub(before.idle).min(total);
+  Some(((total - idle) as f64 / total as f64 * 1000.0).round() / 10.0)
+}
+
+/// Battery state; `None` from `power_status` when the host has no battery.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+  pub on_battery: bool,
+  pub percent: Option<u8>,
+  /// Estimated runtime left; only reported while discharging.
+  pub seconds_remaining: Option<i64>,
+}
+
+impl SystemInfo {
+  /// Fields every platform can fill; the rest start empty.
+  fn base(os: String) -> Self {
+    SystemInfo {
+      os,
+      kernel: None,
+      arch: std::env::consts::ARCH.to_string(),
+      hostname: None,
+      model: None,
+      cpu_model: None,
+      cpu_cores: std::thread::available_parallelism().map_or(1, |n| n.get() as u32),
+      cpu_arch: None,
+      mem_total_bytes: None,
+      is_wsl: false,
+      wsl_distro: None,
+      disk_free_bytes: None,
+      disk_total_bytes: None,
+      uptime_secs: None,
+    }
+  }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
 fn read_first_match(path: &str, prefix: &str) -> Option<String> {
   let contents = fs::read_to_string(path).ok()?;
   contents
@@ -11,77 +104,496 @@ fn read_first_match(path: &str, prefix: &str) -> Option<String> {
     .find_map(|line| line.strip_prefix(prefix).map(|value| value.trim().to_string()))
 }
 
-#[cfg(unix)]
-pub fn system_info_string() -> String {
+#[cfg(all(unix, not(target_os = "macos")))]
+fn collect_system_info() -> SystemInfo {
   let os_pretty = read_first_match("/etc/os-release", "PRETTY_NAME=").map(|value| {
     value.trim_matches('"').to_string()
   });
-  let kernel = fs::read_to_string("/proc/sys/kernel/osrelease").ok().map(|s| s.trim().to_string());
-  let hostname = fs::read_to_string("/proc/sys/kernel/hostname").ok().map(|s| s.trim().to_string());
+  let mut info = SystemInfo::base(os_pretty.unwrap_or_else(|| std::env::consts::OS.to_string()));
+  let read_trimmed = |path: &str| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+  info.kernel = read_trimmed("/proc/sys/kernel/osrelease");
+  info.hostname = read_trimmed("/proc/sys/kernel/hostname");
 
-  let cpu_model = read_first_match("/proc/cpuinfo", "model name\t: ");
+  info.cpu_model = read_first_match("/proc/cpuinfo", "model name\t: ");
   let cpu_cores = fs::read_to_string("/proc/cpuinfo")
     .ok()
-    .map(|s| s.lines().filter(|line| line.starts_with("processor\t:")).count());
-  let mem_total_kb = read_first_match("/proc/meminfo", "MemTotal:")
-    .and_then(|value| value.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()));
-  let mem_total_gb = mem_total_kb.map(|kb| (kb as f64) / 1024.0 / 1024.0);
+    .map(|s| s.lines().filter(|line| line.starts_with("processor\t:")).count() as u32);
+  if let Some(cores) = cpu_cores.filter(|cores| *cores > 0) {
+    info.cpu_cores = cores;
+  }
+  info.mem_total_bytes = read_first_match("/proc/meminfo", "MemTotal:")
+    .and_then(|value| value.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()))
+    .map(|kb| kb * 1024);
 
-  let distro = std::env::var("WSL_DISTRO_NAME").ok();
-  let is_wsl = distro.is_some() || kernel.as_deref().unwrap_or("").to_lowercase().contains("microsoft");
+  info.uptime_secs = read_trimmed("/proc/uptime")
+    .and_then(|s| s.split_whitespace().next()?.parse::<f64>().ok())
+    .map(|secs| secs as u64);
 
-  let mut lines = Vec::new();
-  lines.push(format!("OS: {}", os_pretty.unwrap_or_else(|| std::env::consts::OS.to_string())));
-  lines.push(format!("Kernel: {}", kernel.unwrap_or_else(|| "unknown".to_string())));
-  lines.push(format!("Arch: {}", std::env::consts::ARCH));
-  lines.push(format!("Hostname: {}", hostname.unwrap_or_else(|| "unknown".to_string())));
-  if is_wsl {
-    lines.push("WSL: true".to_string());
-    if let Some(distro_name) = distro {
-      lines.push(format!("WSL Distro: {}", distro_name));
+  info.wsl_distro = std::env::var("WSL_DISTRO_NAME").ok();
+  info.is_wsl = info.wsl_distro.is_some()
+    || info.kernel.as_deref().unwrap_or("").to_lowercase().contains("microsoft");
+  info
+}
+
+/// `/proc/stat` CPU lines: the `cpu` aggregate first, then `cpu0`, `cpu1`, …
+/// Idle includes iowait; guest time is already counted in user.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_proc_stat(text: &str) -> Vec<CpuTimes> {
+  text
+    .lines()
+    .filter(|line| line.starts_with("cpu"))
+    .filter_map(|line| {
+      let fields: Vec<u64> =
+        line.split_whitespace().skip(1).take(8).filter_map(|v| v.parse().ok()).collect();
+      (fields.len() >= 4).then(|| CpuTimes {
+        idle: fields[3] + fields.get(4).copied().unwrap_or(0),
+        total: fields.iter().sum(),
+      })
+    })
+    .collect()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn cpu_times() -> Vec<CpuTimes> {
+  fs::read_to_string("/proc/stat").map(|text| parse_proc_stat(&text)).unwrap_or_default()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn load_averages() -> Option<[f64; 3]> {
+  parse_load_averages(&fs::read_to_string("/proc/loadavg").ok()?)
+}
+
+/// First three numbers in `text` (`/proc/loadavg`, or macOS `vm.loadavg`
+/// which wraps them in braces).
+#[cfg(unix)]
+fn parse_load_averages(text: &str) -> Option<[f64; 3]> {
+  let mut values = text.split_whitespace().filter_map(|value| value.parse::<f64>().ok());
+  Some([values.next()?, values.next()?, values.next()?])
+}
+
+/// Batteries and supplies under `root` (`/sys/class/power_supply`). Several
+/// batteries are combined; on battery means no mains/USB supply is online,
+/// or a battery is discharging when the host exposes no supply at all.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn read_power_supplies(root: &Path) -> Option<PowerStatus> {
+  let (mut batteries, mut percent_sum, mut discharging) = (0u32, 0u32, false);
+  let (mut stored, mut draw) = (0.0f64, 0.0f64);
+  let mut supply_online: Option<bool> = None;
+  for entry in fs::read_dir(root).ok()?.flatten() {
+    let dir = entry.path();
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok().map(|s| s.trim().to_string());
+    let number = |name: &str| read(name).and_then(|value| value.parse::<f64>().ok());
+    match read("type").as_deref() {
+      Some("Battery") => {
+        // Peripheral batteries (mice, headsets) report scope "Device".
+        if read("scope").as_deref() == Some("Device") || read("present").as_deref() == Some("0") {
+          continue;
+        }
+        batteries += 1;
+        percent_sum += number("capacity").map_or(0, |value| value.clamp(0.0, 100.0) as u32);
+        discharging |= read("status").as_deref() == Some("Discharging");
+        // µWh / µW or µAh / µA, both in hours; some drivers report a negative rate.
+        let remaining = number("energy_now")
+          .zip(number("power_now"))
+          .or_else(|| number("charge_now").zip(number("current_now")));
+        if let Some((now, rate)) = remaining {
+          stored += now;
+          draw += rate.abs();
+        }
+      }
+      Some("Mains" | "USB") => {
+        if let Some(online) = read("online") {
+          supply_online = Some(supply_online.unwrap_or(false) || online == "1");
+        }
+      }
+      _ => {}
     }
   }
-  if let Some(model) = cpu_model {
-    lines.push(format!("CPU: {}", model));
+  if batteries == 0 {
+    return None;
   }
-  if let Some(cores) = cpu_cores {
-    lines.push(format!("CPU Cores: {}", cores));
+  let on_battery = supply_online.map_or(discharging, |online| !online);
+  let seconds_remaining =
+    (on_battery && draw > 0.0).then(|| (stored / draw * 3600.0).round() as i64);
+  Some(PowerStatus {
+    on_battery,
+    percent: Some((percent_sum / batteries) as u8),
+    seconds_remaining,
+  })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn collect_power_status() -> Option<PowerStatus> {
+  read_power_supplies(Path::new("/sys/class/power_supply"))
+}
+
+/// Trimmed stdout of a successful command, if non-empty.
+#[cfg(target_os = "macos")]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+  let output = std::process::Command::new(program).args(args).output().ok()?;
+  if !output.status.success() {
+    return None;
   }
-  if let Some(gb) = mem_total_gb {
-    lines.push(format!("Memory: {:.2} GB", gb));
+  let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  (!text.is_empty()).then_some(text)
+}
+
+#[cfg(target_os = "macos")]
+fn sysctl(name: &str) -> Option<String> {
+  command_output("sysctl", &["-n", name])
+}
+
+#[cfg(target_os = "macos")]
+fn load_averages() -> Option<[f64; 3]> {
+  // Looks like "{ 1.52 1.61 1.70 }".
+  parse_load_averages(&sysctl("vm.loadavg")?)
+}
+
+#[cfg(target_os = "macos")]
+fn collect_system_info() -> SystemInfo {
+  let product =
+    command_output("sw_vers", &["-productName"]).unwrap_or_else(|| "macOS".to_string());
+  let os = match command_output("sw_vers", &["-productVersion"]) {
+    Some(version) => match command_output("sw_vers", &["-buildVersion"]) {
+      Some(build) => format!("{} {} ({})", product, version, build),
+      None => format!("{} {}", product, version),
+    },
+    None => product,
+  };
+
+  let mut info = SystemInfo::base(os);
+  info.kernel = sysctl("kern.osrelease");
+  info.hostname = sysctl("kern.hostname");
+  info.model = sysctl("hw.model");
+  info.cpu_model = sysctl("machdep.cpu.brand_string");
+  if let Some(cores) = sysctl("hw.ncpu").and_then(|value| value.parse().ok()) {
+    info.cpu_cores = cores;
   }
+  info.mem_total_bytes = sysctl("hw.memsize").and_then(|value| value.parse().ok());
+  // kern.boottime looks like "{ sec = 1700000000, usec = 0 } Tue Nov 14 ...".
+  let boot_secs = sysctl("kern.boottime").and_then(|value| {
+    let rest = value.split("sec = ").nth(1)?;
+    rest.split(|c: char| !c.is_ascii_digit()).next()?.parse::<u64>().ok()
+  });
+  let now_secs = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_secs())
+    .ok();
+  info.uptime_secs = boot_secs.zip(now_secs).map(|(boot, now)| now.saturating_sub(boot));
+  info
+}
 
-  lines.join("\n")
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+  fn GetDiskFreeSpaceExW(
+    directory: *const u16,
+    free_bytes_available: *mut u64,
+    total_bytes: *mut u64,
+    total_free_bytes: *mut u64,
+  ) -> i32;
+  fn GetTickCount64() -> u64;
+  fn GetSystemPowerStatus(status: *mut SystemPowerStatusRaw) -> i32;
+  // FILETIME is two u32 halves, laid out like a little-endian u64.
+  fn GetSystemTimes(idle: *mut u64, kernel: *mut u64, user: *mut u64) -> i32;
 }
 
+/// Whole-system counters only; Windows has no per-core equivalent here.
 #[cfg(windows)]
-pub fn system_info_string() -> String {
-  let mut lines = Vec::new();
+fn cpu_times() -> Vec<CpuTimes> {
+  let (mut idle, mut kernel, mut user) = (0u64, 0u64, 0u64);
+  // SAFETY: the out-pointers are valid for the duration of the call.
+  if unsafe { GetSystemTimes(&mut idle, &mut kernel, &mut user) } == 0 {
+    return Vec::new();
+  }
+  // Kernel time includes idle time.
+  vec![CpuTimes {
+    idle,
+    total: kernel + user,
+  }]
+}
+
+#[cfg(not(unix))]
+fn load_averages() -> Option<[f64; 3]> {
+  None
+}
+
+/// `SYSTEM_POWER_STATUS`.
+#[cfg(windows)]
+#[repr(C)]
+#[derive(Default)]
+struct SystemPowerStatusRaw {
+  ac_line_status: u8,
+  battery_flag: u8,
+  battery_life_percent: u8,
+  system_status_flag: u8,
+  battery_life_time: u32,
+  battery_full_life_time: u32,
+}
+
+#[cfg(windows)]
+fn collect_power_status() -> Option<PowerStatus> {
+  let mut raw = SystemPowerStatusRaw::default();
+  // SAFETY: `raw` matches SYSTEM_POWER_STATUS and outlives the call.
+  if unsafe { GetSystemPowerStatus(&mut raw) } == 0 {
+    return None;
+  }
+  // Flag 128 means no system battery; 255 means the status is unknown.
+  if raw.battery_flag != 255 && raw.battery_flag & 128 != 0 {
+    return None;
+  }
+  let on_battery = raw.ac_line_status == 0;
+  Some(PowerStatus {
+    on_battery,
+    percent: (raw.battery_life_percent <= 100).then_some(raw.battery_life_percent),
+    // u32::MAX when Windows can't estimate it, which includes charging.
+    seconds_remaining: (on_battery && raw.battery_life_time != u32::MAX)
+      .then(|| i64::from(raw.battery_life_time)),
+  })
+}
+
+#[cfg(windows)]
+fn collect_system_info() -> SystemInfo {
   let os = std::env::var("OS").unwrap_or_else(|_| "Windows".to_string());
+  let mut info = SystemInfo::base(os);
+  info.hostname = std::env::var("COMPUTERNAME").ok();
+  info.cpu_model = std::env::var("PROCESSOR_IDENTIFIER").ok();
+  if let Some(cores) = std::env::var("NUMBER_OF_PROCESSORS")
+    .ok()
+    .and_then(|value| value.parse().ok())
+  {
+    info.cpu_cores = cores;
+  }
+  info.cpu_arch = std::env::var("PROCESSOR_ARCHITECTURE").ok();
+  // SAFETY: no arguments; returns milliseconds since boot.
+  info.uptime_secs = Some(unsafe { GetTickCount64() } / 1000);
+  info
+}
+
+#[cfg(not(any(unix, windows)))]
+fn collect_system_info() -> SystemInfo {
+  SystemInfo::base(std::env::consts::OS.to_string())
+}
+
+#[cfg(any(target_os = "macos", not(any(unix, windows))))]
+fn cpu_times() -> Vec<CpuTimes> {
+  Vec::new()
+}
+
+#[cfg(any(target_os = "macos", not(any(unix, windows))))]
+fn collect_power_status() -> Option<PowerStatus> {
+  None
+}
+
+/// `(free for the current user, total)` bytes on the filesystem holding `path`.
+#[cfg(unix)]
+#[allow(clippy::useless_conversion)] // statvfs field widths differ between platforms
+fn disk_space(path: &Path) -> Option<(u64, u64)> {
+  use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+  let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+  // SAFETY: `statvfs` is plain old data and is fully written on success.
+  let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+  if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+    return None;
+  }
+  let block = u64::from(stat.f_frsize);
+  Some((u64::from(stat.f_bavail) * block, u64::from(stat.f_blocks) * block))
+}
+
+#[cfg(windows)]
+fn disk_space(path: &Path) -> Option<(u64, u64)> {
+  use std::os::windows::ffi::OsStrExt;
+
+  let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+  let (mut free, mut total) = (0u64, 0u64);
+  // SAFETY: `wide` is NUL-terminated and the out-pointers outlive the call.
+  let ok = unsafe {
+    GetDiskFreeSpaceExW(wide.as_ptr(), &mut free, &mut total, std::ptr::null_mut())
+  };
+  (ok != 0).then_some((free, total))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn disk_space(_path: &Path) -> Option<(u64, u64)> {
+  None
+}
+
+fn gather_system_info<R: Runtime>(app: &AppHandle<R>) -> SystemInfo {
+  let mut info = collect_system_info();
+  // The data dir may not exist yet; measure the nearest existing ancestor.
+  let data_dir = app.path().app_data_dir().ok();
+  let space = data_dir
+    .as_deref()
+    .and_then(|dir| dir.ancestors().find(|path| path.exists()))
+    .and_then(disk_space);
+  if let Some((free, total)) = space {
+    info.disk_free_bytes = Some(free);
+    info.disk_total_bytes = Some(total);
+  }
+  info
+}
+
+#[tauri::command]
+pub fn system_info<R: Runtime>(app: AppHandle<R>) -> SystemInfo {
+  gather_system_info(&app)
+}
+
+#[tauri::command]
+pub fn power_status() -> Option<PowerStatus> {
+  collect_power_status()
+}
 
-  lines.push(format!("OS: {}", os));
-  lines.push(format!("Arch: {}", std::env::consts::ARCH));
+/// Load averages plus utilization sampled over `sampleMs` (default 250,
+/// at most 1000).
+#[tauri::command]
+pub async fn cpu_load(sample_ms: Option<u64>) -> CpuLoad {
+  let sample_ms = sample_ms.unwrap_or(DEFAULT_CPU_SAMPLE_MS).clamp(10, MAX_CPU_SAMPLE_MS);
+  let before = cpu_times();
+  if !before.is_empty() {
+    tokio::time::sleep(std::time::Duration::from_millis(sample_ms)).await;
+  }
+  let after = cpu_times();
+  let mut usage = before.iter().zip(&after).map(|(before, after)| busy_percent(before, after));
+  let usage_percent = usage.next().flatten();
+  let per_core_percent = usage.map(|percent| percent.unwrap_or(0.0)).collect();
+  let [load1, load5, load15] = load_averages().map_or([None; 3], |loads| loads.map(Some));
+  CpuLoad {
+    load1,
+    load5,
+    load15,
+    usage_percent,
+    per_core_percent,
+    sample_ms,
+  }
+}
 
-  if let Ok(hostname) = std::env::var("COMPUTERNAME") {
+fn format_gb(bytes: u64) -> String {
+  format!("{:.2} GB", (bytes as f64) / 1024.0 / 1024.0 / 1024.0)
+}
+
+/// Plain-text summary for the About dialog.
+pub fn system_info_string<R: Runtime>(app: &AppHandle<R>) -> String {
+  let info = gather_system_info(app);
+
+  let mut lines = Vec::new();
+  lines.push(format!("OS: {}", info.os));
+  if let Some(kernel) = info.kernel {
+    lines.push(format!("Kernel: {}", kernel));
+  }
+  lines.push(format!("Arch: {}", info.arch));
+  if let Some(hostname) = info.hostname {
     lines.push(format!("Hostname: {}", hostname));
   }
-  if let Ok(cpu) = std::env::var("PROCESSOR_IDENTIFIER") {
-    lines.push(format!("CPU: {}", cpu));
+  if info.is_wsl {
+    lines.push("WSL: true".to_string());
+    if let Some(distro_name) = info.wsl_distro {
+      lines.push(format!("WSL Distro: {}", distro_name));
+    }
+  }
+  if let Some(model) = info.model {
+    lines.push(format!("Model: {}", model));
   }
-  if let Ok(cores) = std::env::var("NUMBER_OF_PROCESSORS") {
-    lines.push(format!("CPU Cores: {}", cores));
+  if let Some(cpu) = info.cpu_model {
+    lines.push(format!("CPU: {}", cpu));
   }
-  if let Ok(arch) = std::env::var("PROCESSOR_ARCHITECTURE") {
+  lines.push(format!("CPU Cores: {}", info.cpu_cores));
+  if let Some(arch) = info.cpu_arch {
     lines.push(format!("CPU Arch: {}", arch));
   }
+  if let Some(bytes) = info.mem_total_bytes {
+    lines.push(format!("Memory: {}", format_gb(bytes)));
+  }
+  if let (Some(free), Some(total)) = (info.disk_free_bytes, info.disk_total_bytes) {
+    lines.push(format!("Disk: {} free of {}", format_gb(free), format_gb(total)));
+  }
+  if let Some(secs) = info.uptime_secs {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    lines.push(format!("Uptime: {}d {}h {}m", days, hours, minutes));
+  }
+  if let Some(power) = collect_power_status() {
+    let source = if power.on_battery { "battery" } else { "mains" };
+    let mut line = format!("Power: {}", source);
+    if let Some(percent) = power.percent {
+      line.push_str(&format!(", {}%", percent));
+    }
+    if let Some(secs) = power.seconds_remaining {
+      line.push_str(&format!(", {}h {}m left", secs / 3600, secs % 3600 / 60));
+    }
+    lines.push(line);
+  }
 
   lines.join("\n")
 }
 
-#[cfg(not(any(unix, windows)))]
-pub fn system_info_string() -> String {
-  let mut lines = Vec::new();
-  lines.push(format!("OS: {}", std::env::consts::OS));
-  lines.push(format!("Arch: {}", std::env::consts::ARCH));
-  lines.join("\n")
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+  use super::*;
+
+  fn supply(root: &Path, name: &str, files: &[(&str, &str)]) {
+    let dir = root.join(name);
+    fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in files {
+      fs::write(dir.join(file), format!("{contents}\n")).unwrap();
+    }
+  }
+
+  #[test]
+  fn combines_batteries_and_ignores_peripherals() {
+    let root = std::env::temp_dir().join(format!("rs485-power-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    supply(&root, "hidpp_battery_0", &[("type", "Battery"), ("scope", "Device")]);
+    assert_eq!(read_power_supplies(&root), None);
+
+    supply(&root, "AC", &[("type", "Mains"), ("online", "0")]);
+    let battery = [
+      ("type", "Battery"),
+      ("status", "Discharging"),
+      ("capacity", "80"),
+      ("energy_now", "40000000"),
+      ("power_now", "10000000"),
+    ];
+    supply(&root, "BAT0", &battery);
+    supply(&root, "BAT1", &[("type", "Battery"), ("status", "Unknown"), ("capacity", "60")]);
+    let status = read_power_supplies(&root);
+
+    supply(&root, "AC", &[("online", "1")]);
+    let charging = read_power_supplies(&root);
+    fs::remove_dir_all(&root).unwrap();
+
+    let expected = PowerStatus {
+      on_battery: true,
+      percent: Some(70),
+      seconds_remaining: Some(4 * 3600),
+    };
+    assert_eq!(status, Some(expected));
+    let expected = PowerStatus {
+      on_battery: false,
+      percent: Some(70),
+      seconds_remaining: None,
+    };
+    assert_eq!(charging, Some(expected));
+  }
+
+  #[test]
+  fn proc_stat_deltas_give_busy_percentages() {
+    let before = parse_proc_stat(
+      "cpu  100 0 100 700 100 0 0 0 0 0\n\
+       cpu0 50 0 50 350 50 0 0 0 0 0\n\
+       cpu1 50 0 50 350 50 0 0 0 0 0\n\
+       intr 12345\n",
+    );
+    let after = parse_proc_stat(
+      "cpu  250 0 150 800 100 0 0 0 0 0\n\
+       cpu0 200 0 100 350 50 0 0 0 0 0\n\
+       cpu1 50 0 50 450 50 0 0 0 0 0\n",
+    );
+    assert_eq!(before.len(), 3);
+    let usage: Vec<_> = before.iter().zip(&after).map(|(b, a)| busy_percent(b, a)).collect();
+    assert_eq!(usage, [Some(66.7), Some(100.0), Some(0.0)]);
+    assert_eq!(busy_percent(&before[0], &before[0]), None);
+
+    assert_eq!(parse_load_averages("0.52 0.58 0.59 1/467 12345"), Some([0.52, 0.58, 0.59]));
+    assert_eq!(parse_load_averages("{ 1.52 1.61 1.70 }"), Some([1.52, 1.61, 1.7]));
+  }
 }
@@ -0,0 +1,234 @@
+//! Bridge from the serial read stream into the telemetry pipeline.
+//! Parses line-delimited frames into metrics and publishes them like API ingest.
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::api_server::{TelemetryBus, TelemetryEvent};
+use crate::frame_parser::FrameParser;
+use crate::serial::{SerialError, SerialState};
+
+/// Longest unterminated frame kept before it is discarded as garbage.
+const MAX_FRAME_BYTES: usize = 64 * 1024;
+
+/// How each frame is turned into a metrics object.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BridgeParser {
+  /// One JSON object per line, e.g. `{"temp":21.5}`.
+  Json,
+  /// `key=value` pairs per line, separated by commas, semicolons or whitespace.
+  KeyValue,
+  /// Binary frames decoded by field layout, split on the parser's delimiter
+  /// or, without one, every `length` bytes.
+  Frame(FrameParser),
+}
+
+/// Payload of `telemetry:parse_error`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ParseError {
+  device_uid: String,
+  line: String,
+  error: String,
+}
+
+/// Active bridge configured by `serial_to_telemetry`.
+pub struct TelemetryBridge {
+  device_uid: String,
+  parser: BridgeParser,
+  store: bool,
+  /// Frame terminator; empty for fixed-length frames.
+  delimiter: Vec<u8>,
+  /// Bytes of the current, not yet terminated frame.
+  pending: Vec<u8>,
+  /// Set after the first "not stored" warning so it isn't logged per sample.
+  warned_no_db: bool,
+}
+
+impl TelemetryBridge {
+  /// Consumes a chunk from the read loop and publishes every completed frame.
+  pub(crate) fn feed<R: Runtime>(&mut self, app: &AppHandle<R>, bytes: &[u8]) {
+    self.pending.extend_from_slice(bytes);
+    while let Some(frame) = self.next_frame() {
+      self.publish_frame(app, &frame);
+    }
+    if self.pending.len() > MAX_FRAME_BYTES {
+      eprintln!("[serial] telemetry frame over {MAX_FRAME_BYTES} bytes discarded");
+      self.pending.clear();
+    }
+  }
+
+  fn next_frame(&mut self) -> Option<Vec<u8>> {
+    let end = if self.delimiter.is_empty() {
+      match &self.parser {
+        BridgeParser::Frame(FrameParser {
+          length: Some(length),
+          ..
+        }) => (self.pending.len() >= *length).then_some(*length)?,
+        _ => return None,
+      }
+    } else {
+      self
+        .pending
+        .windows(self.delimiter.len())
+        .position(|window| window == self.delimiter)?
+    };
+    let frame = self.pending[..end].to_vec();
+    self.pending.drain(..end + self.delimiter.len());
+    Some(frame)
+  }
+
+  fn publish_frame<R: Runtime>(&mut self, app: &AppHandle<R>, frame: &[u8]) {
+    let parsed = match &self.parser {
+      BridgeParser::Frame(parser) => {
+        let parsed = parser.parse(frame);
+        Ok((parsed.metrics, Some(parsed.quality)))
+      }
+      parser => {
+        let line = String::from_utf8_lossy(frame).trim().to_string();
+        if line.is_empty() {
+          return;
+        }
+        parse_line(parser, &line)
+          .map(|metrics| (metrics, None))
+          .map_err(|error| (line, error))
+      }
+    };
+    match parsed {
+      Ok((metrics, quality)) => self.publish(app, metrics, quality),
+      Err((line, error)) => {
+        let _ = app.emit(
+          "telemetry:parse_error",
+          ParseError {
+            device_uid: self.device_uid.clone(),
+            line,
+            error,
+          },
+        );
+      }
+    }
+  }
+
+  fn publish<R: Runtime>(&mut self, app: &AppHandle<R>, metrics: Value, quality: Option<Value>) {
+    let now = chrono::Utc::now();
+    let event = TelemetryEvent {
+      ts: now.to_rfc3339(),
+      device_id: None,
+      device_uid: Some(self.device_uid.clone()),
+      metrics,
+      quality,
+    };
+    if let Some(bus) = app.try_state::<TelemetryBus>() {
+      bus.publish(event.clone());
+      let stored = !self.store || bus.store(self.device_uid.clone(), now, event.clone());
+      if !stored && !self.warned_no_db {
+        eprintln!("[serial] telemetry not stored: database not connected");
+        self.warned_no_db = true;
+      }
+    }
+    let _ = app.emit("telemetry:sample", event);
+  }
+}
+
+fn parse_line(parser: &BridgeParser, line: &str) -> Result<Value, String> {
+  match parser {
+    BridgeParser::Json => match serde_json::from_str(line) {
+      Ok(Value::Object(map)) => Ok(Value::Object(map)),
+      Ok(_) => Err("expected a JSON object".to_string()),
+      Err(err) => Err(format!("invalid JSON: {err}")),
+    },
+    BridgeParser::KeyValue => {
+      let mut map = Map::new();
+      for pair in line
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|pair| !pair.is_empty())
+      {
+        let Some((key, value)) = pair.split_once('=') else {
+          return Err(format!("expected key=value, got \"{pair}\""));
+        };
+        if key.is_empty() {
+          return Err(format!("empty key in \"{pair}\""));
+        }
+        map.insert(key.to_string(), scalar(value));
+      }
+      if map.is_empty() {
+        return Err("no key=value pairs".to_string());
+      }
+      Ok(Value::Object(map))
+    }
+    BridgeParser::Frame(_) => Err("binary frames aren't line-based".to_string()),
+  }
+}
+
+/// Numbers and booleans keep their type so aggregates work; anything else is text.
+fn scalar(value: &str) -> Value {
+  if let Ok(number) = value.parse::<i64>() {
+    return Value::from(number);
+  }
+  if let Some(number) = value.parse::<f64>().ok().filter(|n| n.is_finite()) {
+    return Value::from(number);
+  }
+  match value {
+    "true" => Value::Bool(true),
+    "false" => Value::Bool(false),
+    _ => Value::String(value.to_string()),
+  }
+}
+
+/// Routes frames from `port_id`'s serial stream (`start_serial_stream`) into
+/// the realtime broadcast and, with `store`, into `telemetry_samples`.
+/// Replaces any bridge already running on that port.
+#[tauri::command]
+pub fn serial_to_telemetry(
+  state: State<'_, SerialState>,
+  port_id: Option<String>,
+  device_uid: String,
+  parser: BridgeParser,
+  store: Option<bool>,
+) -> Result<(), SerialError> {
+  let device_uid = device_uid.trim();
+  if device_uid.is_empty() {
+    return Err(SerialError::InvalidInput("device_uid must not be empty".to_string()));
+  }
+  let (delimiter, kind) = match &parser {
+    BridgeParser::Json => (b"\n".to_vec(), "json"),
+    BridgeParser::KeyValue => (b"\n".to_vec(), "keyValue"),
+    BridgeParser::Frame(frame) => {
+      frame.validate()?;
+      let delimiter = frame.delimiter_bytes()?;
+      if delimiter.is_empty() && frame.length.unwrap_or(0) == 0 {
+        return Err(SerialError::InvalidInput(
+          "Frame parser needs a delimiter or a length to split the stream".to_string(),
+        ));
+      }
+      (delimiter, "frame")
+    }
+  };
+  let store = store.unwrap_or(false);
+  let state = state.entry(port_id.as_deref())?;
+  *state.telemetry_bridge.lock()? = Some(TelemetryBridge {
+    device_uid: device_uid.to_string(),
+    parser,
+    store,
+    delimiter,
+    pending: Vec::new(),
+    warned_no_db: false,
+  });
+  eprintln!("[serial] telemetry bridge on device={device_uid} parser={kind} store={store}");
+  Ok(())
+}
+
+/// Returns whether a bridge was running.
+#[tauri::command]
+pub fn stop_serial_to_telemetry(
+  state: State<'_, SerialState>,
+  port_id: Option<String>,
+) -> Result<bool, SerialError> {
+  let state = state.entry(port_id.as_deref())?;
+  let stopped = state.telemetry_bridge.lock()?.take().is_some();
+  if stopped {
+    eprintln!("[serial] telemetry bridge off");
+  }
+  Ok(stopped)
+}
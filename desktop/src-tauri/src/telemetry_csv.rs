@@ -0,0 +1,64 @@
+//! CSV rendering for telemetry exports.
+//! Flattens nested `metrics_json` objects into dotted column names.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+/// Flattens `metrics` into `path → cell`, joining nested object keys with `.`.
+/// Arrays are kept as JSON text and nulls become empty cells.
+pub fn flatten_metrics(metrics: &Value) -> BTreeMap<String, String> {
+  let mut out = BTreeMap::new();
+  flatten_into(&mut out, String::new(), metrics);
+  out
+}
+
+fn flatten_into(out: &mut BTreeMap<String, String>, prefix: String, value: &Value) {
+  match value {
+    Value::Object(map) => {
+      for (key, value) in map {
+        let path = if prefix.is_empty() {
+          key.clone()
+        } else {
+          format!("{prefix}.{key}")
+        };
+        flatten_into(out, path, value);
+      }
+    }
+    Value::Null => {
+      out.insert(prefix, String::new());
+    }
+    Value::String(text) => {
+      out.insert(prefix, text.clone());
+    }
+    other => {
+      out.insert(prefix, other.to_string());
+    }
+  }
+}
+
+/// Sorted union of flattened keys across `metrics`.
+pub fn discover_fields<'a>(metrics: impl IntoIterator<Item = &'a Value>) -> Vec<String> {
+  let mut fields = BTreeSet::new();
+  for value in metrics {
+    fields.extend(flatten_metrics(value).into_keys());
+  }
+  fields.into_iter().collect()
+}
+
+/// Appends one CSV record (RFC 4180 quoting) terminated by CRLF.
+pub fn push_record<'a>(out: &mut String, cells: impl IntoIterator<Item = &'a str>) {
+  for (index, cell) in cells.into_iter().enumerate() {
+    if index > 0 {
+      out.push(',');
+    }
+    if cell.contains([',', '"', '\n', '\r']) {
+      out.push('"');
+      out.push_str(&cell.replace('"', "\"\""));
+      out.push('"');
+    } else {
+      out.push_str(cell);
+    }
+  }
+  out.push_str("\r\n");
+}
@@ -0,0 +1,483 @@
+//! Telemetry storage behind the embedded API server.
+//! Wraps a MySQL or PostgreSQL pool, picked from the `DATABASE_URL` scheme.
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{mysql::MySqlPoolOptions, postgres::PgPoolOptions, MySqlPool, PgPool, QueryBuilder};
+
+use crate::api_server::TelemetryEvent;
+
+/// Insert prefix for `QueryBuilder::push_values`.
+const INSERT_SAMPLES: &str =
+  "INSERT INTO telemetry_samples (device_id, ts, metrics_json, quality_json, source) ";
+
+#[derive(Clone)]
+pub enum Db {
+  MySql(MySqlPool),
+  Postgres(PgPool),
+}
+
+/// Expands `$body` once per backend with `$pool` bound to that pool. Use it for
+/// queries whose SQL is identical on both; `QueryBuilder` emits the right
+/// placeholders (`?` vs `$n`) for each.
+macro_rules! with_pool {
+  ($db:expr, $pool:ident => $body:expr) => {
+    match $db {
+      Db::MySql($pool) => $body,
+      Db::Postgres($pool) => $body,
+    }
+  };
+}
+
+/// Looks up `devices.id` for a uid on `$conn`, creating the row first.
+/// `$insert` is the backend's insert-if-missing statement.
+macro_rules! device_id {
+  ($conn:expr, $insert:expr, $uid:expr) => {{
+    sqlx::query($insert).bind($uid).execute(&mut *$conn).await?;
+    let mut select = QueryBuilder::new("SELECT id FROM devices WHERE device_uid = ");
+    select.push_bind($uid);
+    select.build_query_scalar::<i64>().fetch_one(&mut *$conn).await?
+  }};
+}
+
+/// `DateTime<Utc>` decodes from both MySQL `DATETIME(6)` and Postgres `timestamptz`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct HistoryRow {
+  pub id: i64,
+  pub ts: DateTime<Utc>,
+  pub metrics_json: sqlx::types::Json<Value>,
+  pub quality_json: Option<sqlx::types::Json<Value>>,
+}
+
+/// Position after the last row of a page. Rows sharing a timestamp are
+/// ordered by id; without one, every row at `ts` counts as already seen.
+#[derive(Clone, Copy, Debug)]
+pub struct HistoryCursor {
+  pub ts: DateTime<Utc>,
+  pub id: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistoryFilter {
+  pub start: Option<DateTime<Utc>>,
+  pub end: Option<DateTime<Utc>>,
+  pub cursor: Option<HistoryCursor>,
+  pub descending: bool,
+  pub limit: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DeviceRow {
+  pub uid: String,
+  pub name: Option<String>,
+  pub last_seen: Option<DateTime<Utc>>,
+  pub sample_count: i64,
+}
+
+/// Per-bucket stats for one metric. Values are `None` when no sample in the
+/// bucket had a numeric value for it.
+#[derive(Debug, sqlx::FromRow)]
+pub struct AggregateRow {
+  /// Bucket start, in Unix seconds.
+  pub bucket: i64,
+  pub min_v: Option<f64>,
+  pub max_v: Option<f64>,
+  pub avg_v: Option<f64>,
+  pub last_v: Option<f64>,
+  pub numeric_count: i64,
+  /// Samples that had the metric with a non-numeric value.
+  pub invalid_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct AlarmRuleRow {
+  pub id: i64,
+  pub device_uid: String,
+  pub metric: String,
+  pub op: String,
+  pub threshold: f64,
+  pub hysteresis: f64,
+}
+
+impl Db {
+  pub async fn connect(database_url: &str, max_connections: u32) -> anyhow::Result<Self> {
+    let scheme = database_url.split("://").next().unwrap_or_default();
+    match scheme {
+      "mysql" | "mariadb" => Ok(Db::MySql(
+        MySqlPoolOptions::new()
+          .max_connections(max_connections)
+          .connect(database_url)
+          .await
+          .context("Failed to connect to MySQL")?,
+      )),
+      "postgres" | "postgresql" => Ok(Db::Postgres(
+        PgPoolOptions::new()
+          .max_connections(max_connections)
+          .connect(database_url)
+          .await
+          .context("Failed to connect to PostgreSQL")?,
+      )),
+      other => anyhow::bail!("Unsupported DATABASE_URL scheme: {other}"),
+    }
+  }
+
+  /// Round-trips `SELECT 1` to check the database is reachable.
+  pub async fn ping(&self) -> Result<(), sqlx::Error> {
+    with_pool!(self, pool => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()))
+  }
+
+  /// Applies the bundled `migrations/<backend>` scripts not yet recorded in
+  /// `_sqlx_migrations`. They use `IF NOT EXISTS`, so tables created by
+  /// Alembic are left alone.
+  pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
+    match self {
+      Db::MySql(pool) => sqlx::migrate!("./migrations/mysql").run(pool).await,
+      Db::Postgres(pool) => sqlx::migrate!("./migrations/postgres").run(pool).await,
+    }
+  }
+
+  /// Connections currently checked out of the pool.
+  pub fn pool_in_use(&self) -> u32 {
+    with_pool!(self, pool => pool.size().saturating_sub(pool.num_idle() as u32))
+  }
+
+  pub async fn close(&self) {
+    with_pool!(self, pool => pool.close().await)
+  }
+
+  fn insert_device_sql(&self) -> &'static str {
+    match self {
+      Db::MySql(_) => {
+        "INSERT INTO devices (device_uid) VALUES (?) ON DUPLICATE KEY UPDATE id = id"
+      }
+      Db::Postgres(_) => {
+        "INSERT INTO devices (device_uid) VALUES ($1) ON CONFLICT (device_uid) DO NOTHING"
+      }
+    }
+  }
+
+  /// Every known device with its newest sample time and sample count.
+  pub async fn devices(&self) -> Result<Vec<DeviceRow>, sqlx::Error> {
+    with_pool!(self, pool => {
+      sqlx::query_as::<_, DeviceRow>(
+        "SELECT d.device_uid AS uid, d.name, MAX(t.ts) AS last_seen, \
+         COUNT(t.id) AS sample_count \
+         FROM devices d \
+         LEFT JOIN telemetry_samples t ON t.device_id = d.id \
+         GROUP BY d.id, d.device_uid, d.name \
+         ORDER BY d.device_uid",
+      )
+      .fetch_all(pool)
+      .await
+    })
+  }
+
+  /// One page of samples in `(ts, id)` order.
+  pub async fn history(
+    &self,
+    device_uid: &str,
+    filter: HistoryFilter,
+  ) -> Result<Vec<HistoryRow>, sqlx::Error> {
+    self.history_fields(device_uid, filter, &[]).await
+  }
+
+  /// Like `history`, but when `fields` is non-empty `metrics_json` holds only
+  /// those top-level keys (`null` where a sample lacks one), extracted in the
+  /// query. `fields` must already be validated as plain keys (letters,
+  /// digits, `_`, `-`, `.`) since they are inlined into the SQL.
+  pub async fn history_fields(
+    &self,
+    device_uid: &str,
+    filter: HistoryFilter,
+    fields: &[String],
+  ) -> Result<Vec<HistoryRow>, sqlx::Error> {
+    let (past, order) = if filter.descending { ("<", "DESC") } else { (">", "ASC") };
+    let metrics = if fields.is_empty() {
+      "t.metrics_json".to_string()
+    } else {
+      let pairs: Vec<String> = match self {
+        Db::MySql(_) => fields
+          .iter()
+          .map(|field| format!("'{field}', JSON_EXTRACT(t.metrics_json, '$.\"{field}\"')"))
+          .collect(),
+        Db::Postgres(_) => fields
+          .iter()
+          .map(|field| format!("'{field}', t.metrics_json -> '{field}'"))
+          .collect(),
+      };
+      let build = match self {
+        Db::MySql(_) => "JSON_OBJECT",
+        Db::Postgres(_) => "jsonb_build_object",
+      };
+      format!("{build}({}) AS metrics_json", pairs.join(", "))
+    };
+    with_pool!(self, pool => {
+      let mut builder = QueryBuilder::new(format!(
+        "SELECT t.id, t.ts, {metrics}, t.quality_json \
+         FROM telemetry_samples t \
+         JOIN devices d ON t.device_id = d.id \
+         WHERE d.device_uid = "
+      ));
+      builder.push_bind(device_uid);
+      if let Some(start) = filter.start {
+        builder.push(" AND t.ts >= ");
+        builder.push_bind(start);
+      }
+      if let Some(end) = filter.end {
+        builder.push(" AND t.ts <= ");
+        builder.push_bind(end);
+      }
+      match filter.cursor {
+        Some(HistoryCursor { ts, id: Some(id) }) => {
+          builder.push(format!(" AND (t.ts {past} "));
+          builder.push_bind(ts);
+          builder.push(" OR (t.ts = ");
+          builder.push_bind(ts);
+          builder.push(format!(" AND t.id {past} "));
+          builder.push_bind(id);
+          builder.push("))");
+        }
+        Some(HistoryCursor { ts, id: None }) => {
+          builder.push(format!(" AND t.ts {past} "));
+          builder.push_bind(ts);
+        }
+        None => {}
+      }
+      builder.push(format!(" ORDER BY t.ts {order}, t.id {order} LIMIT "));
+      builder.push_bind(filter.limit);
+      builder.build_query_as::<HistoryRow>().fetch_all(pool).await
+    })
+  }
+
+  /// Buckets samples into `interval_s`-second windows and aggregates `metric`
+  /// from `metrics_json`. `metric` must already be validated as a plain key
+  /// (letters, digits, `_`, `-`, `.`) since it is inlined into the JSON path.
+  pub async fn aggregate(
+    &self,
+    device_uid: &str,
+    metric: &str,
+    interval_s: i64,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+  ) -> Result<Vec<AggregateRow>, sqlx::Error> {
+    let (bucket, value, present) = match self {
+      Db::MySql(_) => {
+        let path = format!("JSON_EXTRACT(t.metrics_json, '$.\"{metric}\"')");
+        (
+          format!("CAST(FLOOR(UNIX_TIMESTAMP(t.ts) / {interval_s}) * {interval_s} AS SIGNED)"),
+          format!(
+            "CASE WHEN JSON_TYPE({path}) IN ('INTEGER', 'UNSIGNED INTEGER', 'DOUBLE', 'DECIMAL') \
+             THEN CAST({path} AS DOUBLE) END"
+          ),
+          format!("{path} IS NOT NULL"),
+        )
+      }
+      Db::Postgres(_) => (
+        format!("CAST(FLOOR(EXTRACT(EPOCH FROM t.ts) / {interval_s}) * {interval_s} AS BIGINT)"),
+        format!(
+          "CASE WHEN jsonb_typeof(t.metrics_json -> '{metric}') = 'number' \
+           THEN CAST(t.metrics_json ->> '{metric}' AS DOUBLE PRECISION) END"
+        ),
+        format!("t.metrics_json -> '{metric}' IS NOT NULL"),
+      ),
+    };
+    with_pool!(self, pool => {
+      let mut builder = QueryBuilder::new(format!(
+        "SELECT r.bucket, MIN(r.v) AS min_v, MAX(r.v) AS max_v, AVG(r.v) AS avg_v, \
+         MAX(CASE WHEN r.rn = 1 THEN r.v END) AS last_v, \
+         COUNT(r.v) AS numeric_count, \
+         COUNT(CASE WHEN r.present AND r.v IS NULL THEN 1 END) AS invalid_count \
+         FROM (SELECT s.*, ROW_NUMBER() OVER (PARTITION BY s.bucket \
+           ORDER BY s.v IS NULL, s.ts DESC, s.id DESC) AS rn \
+         FROM (SELECT t.id, t.ts, {bucket} AS bucket, {value} AS v, {present} AS present \
+           FROM telemetry_samples t \
+           JOIN devices d ON t.device_id = d.id \
+           WHERE d.device_uid = "
+      ));
+      builder.push_bind(device_uid);
+      if let Some(start) = start {
+        builder.push(" AND t.ts >= ");
+        builder.push_bind(start);
+      }
+      if let Some(end) = end {
+        builder.push(" AND t.ts <= ");
+        builder.push_bind(end);
+      }
+      builder.push(") s) r GROUP BY r.bucket ORDER BY r.bucket");
+      builder.build_query_as::<AggregateRow>().fetch_all(pool).await
+    })
+  }
+
+  /// Stores one sample, creating the device on first use.
+  /// Returns `(device_id, sample_id)`.
+  pub async fn insert_sample(
+    &self,
+    device_uid: &str,
+    ts: DateTime<Utc>,
+    event: &TelemetryEvent,
+  ) -> Result<(i64, i64), sqlx::Error> {
+    let insert_device = self.insert_device_sql();
+    let metrics = sqlx::types::Json(&event.metrics);
+    let quality = event.quality.as_ref().map(sqlx::types::Json);
+    match self {
+      Db::MySql(pool) => {
+        let mut tx = pool.begin().await?;
+        let device_id = device_id!(tx, insert_device, device_uid);
+        let result = sqlx::query(
+          "INSERT INTO telemetry_samples (device_id, ts, metrics_json, quality_json, source) \
+           VALUES (?, ?, ?, ?, 'api')",
+        )
+        .bind(device_id)
+        .bind(ts)
+        .bind(metrics)
+        .bind(quality)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok((device_id, result.last_insert_id() as i64))
+      }
+      Db::Postgres(pool) => {
+        let mut tx = pool.begin().await?;
+        let device_id = device_id!(tx, insert_device, device_uid);
+        let id = sqlx::query_scalar(
+          "INSERT INTO telemetry_samples (device_id, ts, metrics_json, quality_json, source) \
+           VALUES ($1, $2, $3, $4, 'api') RETURNING id",
+        )
+        .bind(device_id)
+        .bind(ts)
+        .bind(metrics)
+        .bind(quality)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok((device_id, id))
+      }
+    }
+  }
+
+  /// Deletes samples older than `before`, for one device or (`None`) all of
+  /// them, `batch_rows` at a time so no single statement locks a large table
+  /// for long. Returns the number of rows deleted.
+  pub async fn delete_before(
+    &self,
+    device_uid: Option<&str>,
+    before: DateTime<Utc>,
+    batch_rows: i64,
+  ) -> Result<u64, sqlx::Error> {
+    // MySQL has `DELETE ... LIMIT`; Postgres needs the limit in a subquery.
+    let (prefix, suffix) = match self {
+      Db::MySql(_) => ("DELETE FROM telemetry_samples WHERE ts < ", ""),
+      Db::Postgres(_) => (
+        "DELETE FROM telemetry_samples WHERE id IN \
+         (SELECT id FROM telemetry_samples WHERE ts < ",
+        ")",
+      ),
+    };
+    let mut deleted = 0;
+    loop {
+      let affected = with_pool!(self, pool => {
+        let mut builder = QueryBuilder::new(prefix);
+        builder.push_bind(before);
+        if let Some(device_uid) = device_uid {
+          builder.push(" AND device_id IN (SELECT id FROM devices WHERE device_uid = ");
+          builder.push_bind(device_uid);
+          builder.push(")");
+        }
+        builder.push(" LIMIT ");
+        builder.push_bind(batch_rows);
+        builder.push(suffix);
+        builder.build().execute(pool).await?.rows_affected()
+      });
+      deleted += affected;
+      if affected < batch_rows as u64 {
+        return Ok(deleted);
+      }
+    }
+  }
+
+  /// Every alarm rule, oldest first.
+  pub async fn alarm_rules(&self) -> Result<Vec<AlarmRuleRow>, sqlx::Error> {
+    with_pool!(self, pool => {
+      sqlx::query_as::<_, AlarmRuleRow>(
+        "SELECT id, device_uid, metric, op, threshold, hysteresis FROM alarm_rules ORDER BY id",
+      )
+      .fetch_all(pool)
+      .await
+    })
+  }
+
+  /// Stores a rule and returns its id.
+  pub async fn insert_alarm_rule(
+    &self,
+    device_uid: &str,
+    metric: &str,
+    op: &str,
+    threshold: f64,
+    hysteresis: f64,
+  ) -> Result<i64, sqlx::Error> {
+    match self {
+      Db::MySql(pool) => {
+        let result = sqlx::query(
+          "INSERT INTO alarm_rules (device_uid, metric, op, threshold, hysteresis) \
+           VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(device_uid)
+        .bind(metric)
+        .bind(op)
+        .bind(threshold)
+        .bind(hysteresis)
+        .execute(pool)
+        .await?;
+        Ok(result.last_insert_id() as i64)
+      }
+      Db::Postgres(pool) => {
+        sqlx::query_scalar(
+          "INSERT INTO alarm_rules (device_uid, metric, op, threshold, hysteresis) \
+           VALUES ($1, $2, $3, $4, $5) RETURNING id",
+        )
+        .bind(device_uid)
+        .bind(metric)
+        .bind(op)
+        .bind(threshold)
+        .bind(hysteresis)
+        .fetch_one(pool)
+        .await
+      }
+    }
+  }
+
+  /// Returns whether a rule was deleted.
+  pub async fn delete_alarm_rule(&self, id: i64) -> Result<bool, sqlx::Error> {
+    with_pool!(self, pool => {
+      let mut builder = QueryBuilder::new("DELETE FROM alarm_rules WHERE id = ");
+      builder.push_bind(id);
+      builder.build().execute(pool).await.map(|result| result.rows_affected() > 0)
+    })
+  }
+
+  /// Stores `samples` in one transaction. Returns `(device_id, rows_inserted)`.
+  pub async fn insert_batch(
+    &self,
+    device_uid: &str,
+    samples: &[(DateTime<Utc>, TelemetryEvent)],
+  ) -> Result<(i64, u64), sqlx::Error> {
+    let insert_device = self.insert_device_sql();
+    with_pool!(self, pool => {
+      let mut tx = pool.begin().await?;
+      let device_id = device_id!(tx, insert_device, device_uid);
+      let mut builder = QueryBuilder::new(INSERT_SAMPLES);
+      builder.push_values(samples, |mut row, (ts, event)| {
+        row
+          .push_bind(device_id)
+          .push_bind(*ts)
+          .push_bind(sqlx::types::Json(&event.metrics))
+          .push_bind(event.quality.as_ref().map(sqlx::types::Json))
+          .push_bind("api");
+      });
+      let inserted = builder.build().execute(&mut *tx).await?.rows_affected();
+      tx.commit().await?;
+      Ok((device_id, inserted))
+    })
+  }
+}
@@ -0,0 +1,118 @@
+//! InfluxDB line-protocol rendering for telemetry exports.
+//! One line per sample; nested metric keys are flattened with `.` like the CSV export.
+
+use serde_json::Value;
+
+/// Appends `measurement,device=<uid> <fields> <ts_ns>\n`. Numbers are
+/// written as floats so a field keeps one type across samples; other
+/// values become string fields when `strings` is set and are skipped
+/// otherwise. Nulls are always skipped, and a sample left with no fields
+/// produces no line, since InfluxDB rejects those.
+pub fn push_line(
+  out: &mut String,
+  measurement: &str,
+  device_uid: &str,
+  metrics: &Value,
+  strings: bool,
+  ts_ns: i64,
+) {
+  let mut fields = String::new();
+  push_fields(&mut fields, "", metrics, strings);
+  if fields.is_empty() {
+    return;
+  }
+  push_escaped(out, measurement, &[',', ' ']);
+  out.push_str(",device=");
+  push_escaped(out, device_uid, &[',', '=', ' ']);
+  out.push(' ');
+  out.push_str(&fields);
+  out.push(' ');
+  out.push_str(&ts_ns.to_string());
+  out.push('\n');
+}
+
+fn push_fields(out: &mut String, prefix: &str, value: &Value, strings: bool) {
+  let rendered = match value {
+    Value::Object(map) => {
+      for (key, value) in map {
+        let path = if prefix.is_empty() {
+          key.clone()
+        } else {
+          format!("{prefix}.{key}")
+        };
+        push_fields(out, &path, value, strings);
+      }
+      return;
+    }
+    Value::Null => return,
+    Value::Number(number) => match number.as_f64() {
+      Some(number) if number.is_finite() => format!("{number:?}"),
+      _ => return,
+    },
+    _ if !strings => return,
+    Value::String(text) => quote(text),
+    other => quote(&other.to_string()),
+  };
+  // A top-level scalar has no key; `metrics` is always an object in practice.
+  if prefix.is_empty() {
+    return;
+  }
+  if !out.is_empty() {
+    out.push(',');
+  }
+  push_escaped(out, prefix, &[',', '=', ' ']);
+  out.push('=');
+  out.push_str(&rendered);
+}
+
+fn quote(text: &str) -> String {
+  let mut quoted = String::with_capacity(text.len() + 2);
+  quoted.push('"');
+  push_escaped(&mut quoted, text, &['"', '\\']);
+  quoted.push('"');
+  quoted
+}
+
+/// Backslash-escapes `special`. Line breaks can't be escaped in line
+/// protocol, so they become spaces.
+fn push_escaped(out: &mut String, text: &str, special: &[char]) {
+  for c in text.chars() {
+    match c {
+      '\n' | '\r' => out.push(' '),
+      c if special.contains(&c) => {
+        out.push('\\');
+        out.push(c);
+      }
+      c => out.push(c),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_escaped_fields_and_skips_non_numeric_on_request() {
+    let metrics = serde_json::json!({
+      "voltage": 12,
+      "temp c": 21.5,
+      "status": "ok \"fine\"",
+      "nested": { "a=b": 1.25 },
+      "missing": null,
+    });
+    let mut out = String::new();
+    push_line(&mut out, "telemetry", "dev 1", &metrics, true, 1_700_000_000_000_000_000);
+    assert_eq!(
+      out,
+      "telemetry,device=dev\\ 1 nested.a\\=b=1.25,status=\"ok \\\"fine\\\"\",temp\\ c=21.5,\
+       voltage=12.0 1700000000000000000\n"
+    );
+
+    let mut numeric = String::new();
+    push_line(&mut numeric, "telemetry", "dev", &metrics, false, 1);
+    assert!(!numeric.contains("status"));
+    push_line(&mut numeric, "telemetry", "dev", &serde_json::json!({ "s": "x" }), false, 2);
+    assert_eq!(numeric.lines().count(), 1);
+  }
+}
@@ -82,7 +82,8 @@ export function DeviceConfiguration() {
         parity: string;
         stopBits: string;
         dataBits: number;
-        timeoutMs: number;
+        readTimeoutMs: number;
+        writeTimeoutMs: number;
       }>("open_serial_port", {
         config: {
           port,
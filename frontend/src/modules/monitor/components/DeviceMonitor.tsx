@@ -9,6 +9,7 @@ import { Pill } from "../../../shared/components/Pill";
 import { PrimaryButton } from "../../../shared/components/PrimaryButton";
 import { StatTile } from "../../../shared/components/StatTile";
 import { PageShell } from "../../ui/components/PageShell";
+import { invokeErrorMessage } from "../../../shared/lib/tauri";
 import {
   appendLog,
   clearLog,
@@ -326,7 +327,7 @@ export function DeviceMonitor() {
         )
       );
     } catch (error) {
-      const errText = String(error);
+      const errText = invokeErrorMessage(error);
       if (errText.includes("Serial port not open")) {
         dispatch(
           appendLog({ deviceId: logDeviceId, entry: "[Error] Write failed: Serial port not open." })
@@ -448,7 +449,7 @@ export function DeviceMonitor() {
       );
     } catch (error) {
       dispatch(
-        appendLog({ deviceId: logDeviceId, entry: `[Error] Read failed: ${String(error)}` })
+        appendLog({ deviceId: logDeviceId, entry: `[Error] Read failed: ${invokeErrorMessage(error)}` })
       );
       dispatch(
         addEvent(
@@ -456,7 +457,7 @@ export function DeviceMonitor() {
             deviceId: logDeviceId,
             type: "Serial",
             severity: "error",
-            message: `Read failed: ${String(error)}`,
+            message: `Read failed: ${invokeErrorMessage(error)}`,
             source: "serial",
           })
         )
@@ -8,3 +8,14 @@
 export function isTauri(): boolean {
   return typeof window !== "undefined" && !!(window as any).__TAURI_INTERNALS__;
 }
+
+/**
+ * Extracts a human-readable message from a rejected `invoke` call.
+ * Serial commands reject with `{ kind, message }`; others reject with a plain string.
+ */
+export function invokeErrorMessage(error: unknown): string {
+  if (error && typeof error === "object" && "message" in error) {
+    return String((error as { message: unknown }).message);
+  }
+  return String(error);
+}